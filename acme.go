@@ -0,0 +1,109 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"log"
+	"os"
+	"strings"
+
+	"github.com/miekg/dns"
+	"github.com/spf13/viper"
+)
+
+func hasTXT(rrs []dns.RR, value string) bool {
+	for _, rr := range rrs {
+		if txt, ok := rr.(*dns.TXT); ok && strings.Join(txt.Txt, "") == value {
+			return true
+		}
+	}
+	return false
+}
+
+// acmeArgs returns the challenge record name, the token, and whether the
+// record should be removed. They are taken from the --acme-domain, --token
+// and --cleanup flags, the environment of a certbot hook, or the arguments
+// of a lego exec hook ('present|cleanup <fqdn> <value>').
+func acmeArgs(args []string) (string, string, bool, bool) {
+	if len(args) == 3 && (args[0] == "present" || args[0] == "cleanup") {
+		return dns.Fqdn(args[1]), args[2], args[0] == "cleanup", true
+	} else if len(args) != 0 {
+		return "", "", false, false
+	}
+
+	domain := viper.GetString("acme.domain")
+	if len(domain) == 0 {
+		domain = os.Getenv("CERTBOT_DOMAIN")
+	}
+	token := viper.GetString("acme.token")
+	if len(token) == 0 {
+		token = os.Getenv("CERTBOT_VALIDATION")
+	}
+	if len(domain) == 0 || len(token) == 0 {
+		return "", "", false, false
+	}
+
+	domain = strings.TrimPrefix(domain, "*.")
+	return dns.Fqdn("_acme-challenge." + domain), token, viper.GetBool("acme.cleanup"), true
+}
+
+func acme(args []string) int {
+	name, token, cleanup, ok := acmeArgs(args)
+	if !ok {
+		log.Print("Usage: update-dns acme --acme-domain <domain> --token <token> [--cleanup] [--wait]")
+		return 1
+	}
+
+	txt := &dns.TXT{Hdr: dns.RR_Header{Name: name,
+		Ttl:    uint32(viper.GetInt("acme.ttl")),
+		Rrtype: dns.TypeTXT,
+		Class:  dns.ClassINET},
+		Txt: []string{token}}
+
+	// Several challenges can be pending for the same name (e.g. for a
+	// wildcard and the bare domain), so only touch our own record
+	action := "append"
+	if cleanup {
+		action = "remove"
+	}
+
+	zone := getZone(name)
+	if err := update(zone, []operation{{action, txt}}); err != nil {
+		log.Printf("Unable to send update: %s", err)
+		return exitCode(err)
+	}
+
+	if cleanup {
+		infof("Removed challenge record %s", name)
+		return 0
+	}
+	infof("Created challenge record %s", name)
+
+	if viper.GetBool("acme.wait") {
+		servers, err := nameservers(zone)
+		if err != nil {
+			log.Printf("Unable to find nameservers: %s", err)
+			return 1
+		}
+		check := func(rrs []dns.RR) bool { return hasTXT(rrs, token) }
+		err = waitFor(servers, name, dns.TypeTXT, check, viper.GetDuration("acme.wait-timeout"))
+		if err != nil {
+			log.Print(err)
+			return 1
+		}
+	}
+	return 0
+}
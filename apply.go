@@ -0,0 +1,246 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"fmt"
+	"io"
+	"log"
+	"os"
+	"strings"
+
+	"github.com/miekg/dns"
+	"github.com/spf13/viper"
+)
+
+// A desiredState is the contents of a file describing the records that
+// should be in a zone.
+type desiredState struct {
+	zone    string
+	records []dns.RR
+}
+
+// loadDesired reads a desired state file (in any format supported by viper)
+// of the form:
+//
+//	zone: example.org
+//	ttl: 3600
+//	records:
+//	  - www 300 A 192.0.2.1
+//	  - "@ MX 10 mail"
+//
+// Record names are relative to the zone unless they end in a dot.
+func loadDesired(file string) (*desiredState, error) {
+	v := viper.New()
+	v.SetConfigFile(file)
+	v.SetDefault("ttl", 3600)
+	if err := v.ReadInConfig(); err != nil {
+		return nil, err
+	}
+
+	if len(v.GetString("zone")) == 0 {
+		return nil, fmt.Errorf("missing zone")
+	}
+
+	state := &desiredState{zone: dns.Fqdn(v.GetString("zone"))}
+	prefix := fmt.Sprintf("$ORIGIN %s\n$TTL %d\n", state.zone, v.GetInt("ttl"))
+	for _, record := range v.GetStringSlice("records") {
+		rr, err := dns.NewRR(prefix + record)
+		if err != nil {
+			return nil, fmt.Errorf("invalid record '%s': %s", record, err)
+		} else if rr == nil {
+			continue
+		}
+		if !dns.IsSubDomain(state.zone, rr.Header().Name) {
+			return nil, fmt.Errorf("record '%s' is not in zone %s", record, state.zone)
+		}
+		state.records = append(state.records, rr)
+	}
+	return state, nil
+}
+
+// managedType returns true for record types that can be managed by apply.
+// The SOA and DNSSEC records are maintained by the server.
+func managedType(rrtype uint16) bool {
+	switch rrtype {
+	case dns.TypeSOA, dns.TypeRRSIG, dns.TypeNSEC, dns.TypeNSEC3,
+		dns.TypeNSEC3PARAM, dns.TypeDNSKEY, dns.TypeCDS, dns.TypeCDNSKEY:
+		return false
+	}
+	return true
+}
+
+func managedRecord(rr dns.RR) bool {
+	return managedType(rr.Header().Rrtype) && !isOwnershipName(rr.Header().Name)
+}
+
+func groupRRsets(rrs []dns.RR) map[string][]dns.RR {
+	sets := make(map[string][]dns.RR)
+	for _, rr := range rrs {
+		sets[rrsetKey(rr)] = append(sets[rrsetKey(rr)], rr)
+	}
+	return sets
+}
+
+func findRecord(rrs []dns.RR, rr dns.RR) dns.RR {
+	for _, r := range rrs {
+		if dns.IsDuplicate(r, rr) {
+			return r
+		}
+	}
+	return nil
+}
+
+// planChanges returns the operations needed to turn the live records into
+// the desired ones. RRsets in the desired state replace the live ones; other
+// RRsets are only deleted when pruning. Deletions come before additions, and
+// TTL changes show up as a deletion of the old record and an addition of the
+// new one.
+func planChanges(state *desiredState, live []dns.RR, prune bool) []operation {
+	var removes, adds []operation
+
+	liveSets := groupRRsets(live)
+	desiredSets := groupRRsets(state.records)
+
+	for _, rr := range live {
+		if !managedRecord(rr) {
+			continue
+		}
+
+		desired, ok := desiredSets[rrsetKey(rr)]
+		if !ok {
+			// Never prune the NS records at the apex; the zone needs them
+			apexNS := rr.Header().Rrtype == dns.TypeNS &&
+				strings.EqualFold(rr.Header().Name, state.zone)
+			if prune && !apexNS {
+				removes = append(removes, operation{"remove", rr})
+			}
+			continue
+		}
+
+		if d := findRecord(desired, rr); d == nil || d.Header().Ttl != rr.Header().Ttl {
+			removes = append(removes, operation{"remove", rr})
+		}
+	}
+
+	for _, rr := range state.records {
+		if !managedRecord(rr) {
+			continue
+		}
+		l := findRecord(liveSets[rrsetKey(rr)], rr)
+		if l == nil || l.Header().Ttl != rr.Header().Ttl {
+			adds = append(adds, operation{"append", rr})
+		}
+	}
+
+	return append(removes, adds...)
+}
+
+// planApply loads the desired state file and compares it to the live zone.
+// The returned operations include a prerequisite on the current SOA record,
+// so they are only applied if the zone hasn't changed in the meantime.
+func planApply(file string) (*desiredState, []operation, error) {
+	state, err := loadDesired(file)
+	if err != nil {
+		return nil, nil, fmt.Errorf("Unable to load %s: %s", file, err)
+	}
+
+	live, err := transfer(state.zone)
+	if err != nil {
+		return nil, nil, fmt.Errorf("Zone transfer failed: %s", err)
+	}
+
+	ops := planChanges(state, live, viper.GetBool("prune"))
+	if len(ops) > 0 && len(live) > 0 {
+		if soa, ok := live[0].(*dns.SOA); ok {
+			ops = append([]operation{{"yxrr", soa}}, ops...)
+		}
+	}
+	return state, ops, nil
+}
+
+func apply(args []string) int {
+	if len(args) != 1 {
+		log.Print("Usage: update-dns apply [--prune] <file>")
+		return 1
+	}
+
+	state, ops, err := planApply(args[0])
+	if err != nil {
+		log.Print(err)
+		return 1
+	} else if len(ops) == 0 {
+		infof("Zone %s is already up to date", state.zone)
+		return 0
+	}
+
+	if err := checkGuards(state.zone, ops, true); err != nil {
+		log.Printf("Not sending update: %s", err)
+		return 1
+	}
+	err = update(state.zone, ops)
+	if err != nil {
+		log.Printf("Unable to send update: %s", err)
+		return exitCode(err)
+	}
+
+	infof("Applied %d changes to zone %s", len(ops)-1, state.zone)
+	return 0
+}
+
+// printDiff writes the changes in ops to w as a unified diff of the zone,
+// returning the number of changed records.
+func printDiff(w io.Writer, state *desiredState, file string, ops []operation) int {
+	var removed, added []dns.RR
+	for _, op := range ops {
+		switch op.action {
+		case "remove":
+			removed = append(removed, op.rr)
+		case "append":
+			added = append(added, op.rr)
+		}
+	}
+	changes := len(removed) + len(added)
+	if changes == 0 {
+		return 0
+	}
+
+	color := useColor(w)
+	fmt.Fprintln(w, colorize(color, colorBold, fmt.Sprintf("--- %s (%s)", state.zone, setting(state.zone, "server"))))
+	fmt.Fprintln(w, colorize(color, colorBold, "+++ "+file))
+	for _, line := range formatChanges(w, removed, added) {
+		fmt.Fprintln(w, line)
+	}
+	return changes
+}
+
+func diff(args []string) int {
+	if len(args) != 1 {
+		log.Print("Usage: update-dns diff [--prune] <file>")
+		return 1
+	}
+
+	state, ops, err := planApply(args[0])
+	if err != nil {
+		log.Print(err)
+		return 1
+	}
+
+	if printDiff(os.Stdout, state, args[0], ops) > 0 {
+		return 1
+	}
+	return 0
+}
@@ -0,0 +1,56 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"testing"
+
+	"github.com/miekg/dns"
+)
+
+func TestPlanChanges(t *testing.T) {
+	testConfig(t, nil)
+	soa := mustRR(t, "example.org. 3600 IN SOA ns.example.org. admin.example.org. 1 7200 3600 1209600 300")
+	ns := mustRR(t, "example.org. 3600 IN NS ns.example.org.")
+	www := mustRR(t, "www.example.org. 300 IN A 192.0.2.1")
+	old := mustRR(t, "old.example.org. 300 IN A 192.0.2.9")
+	mx := mustRR(t, "example.org. 3600 IN MX 10 mail.example.org.")
+	live := []dns.RR{soa, ns, www, old, mx}
+
+	www600 := mustRR(t, "www.example.org. 600 IN A 192.0.2.1")
+	www2 := mustRR(t, "www.example.org. 600 IN A 192.0.2.2")
+	state := &desiredState{zone: "example.org.", records: []dns.RR{www600, www2, mx}}
+
+	// The TTL change replaces the record; the unchanged MX is left alone
+	checkOps(t, planChanges(state, live, false), []operation{
+		{"remove", www},
+		{"append", www600},
+		{"append", www2},
+	})
+
+	// Pruning deletes the records not in the desired state, except for
+	// the SOA and apex NS records
+	checkOps(t, planChanges(state, live, true), []operation{
+		{"remove", www},
+		{"remove", old},
+		{"append", www600},
+		{"append", www2},
+	})
+
+	if ops := planChanges(&desiredState{zone: "example.org.", records: live}, live, true); len(ops) != 0 {
+		t.Errorf("got changes for the live records: %q", formatOps(ops))
+	}
+}
@@ -0,0 +1,156 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"encoding/json"
+	"fmt"
+	"log"
+	"os"
+	"path/filepath"
+	"time"
+
+	"github.com/miekg/dns"
+	"github.com/spf13/viper"
+)
+
+// An auditEntry describes an update sent to a server, for the audit log.
+// Unlike the history, the audit log is only ever appended to (and
+// rotated), and says who sent the update with which key.
+type auditEntry struct {
+	Time       time.Time       `json:"time"`
+	User       string          `json:"user"`
+	SudoUser   string          `json:"sudo_user,omitempty"`
+	Host       string          `json:"host"`
+	Command    string          `json:"command,omitempty"`
+	Key        string          `json:"key,omitempty"`
+	Zone       string          `json:"zone"`
+	Server     string          `json:"server"`
+	Operations []jsonOperation `json:"operations"`
+	Old        []string        `json:"old"`
+	New        []string        `json:"new"`
+	Rcode      string          `json:"rcode,omitempty"`
+	Result     string          `json:"result"`
+}
+
+func auditEnabled() bool {
+	return viper.GetBool("audit.enabled")
+}
+
+func auditFile() string {
+	if file := viper.GetString("audit.file"); len(file) > 0 {
+		return file
+	}
+	return filepath.Join(os.Getenv("HOME"), ".update-dns", "audit")
+}
+
+// auditUpdate appends an entry for the update of zone with ops to the audit
+// log. Dry runs and updates that were skipped because they changed
+// nothing aren't logged. The old and new records are only known if state
+// is.
+func auditUpdate(zone string, ops []operation, state *journalEntry, status string,
+	r *dns.Msg, err error) {
+	if !auditEnabled() || status == "dry-run" || status == "unchanged" {
+		return
+	}
+
+	host, _ := os.Hostname()
+	e := auditEntry{
+		Time:       time.Now(),
+		User:       currentUser(),
+		SudoUser:   os.Getenv("SUDO_USER"),
+		Host:       host,
+		Command:    command,
+		Key:        tsigName(zone),
+		Zone:       zone,
+		Server:     setting(zone, "server"),
+		Operations: []jsonOperation{},
+		Old:        []string{},
+		New:        []string{},
+		Result:     "ok",
+	}
+	for _, op := range ops {
+		e.Operations = append(e.Operations, jsonOperation{op.action, op.rr.String()})
+	}
+	if state != nil {
+		removed, added := recordChanges(state.before, ops)
+		e.Old, e.New = recordStrings(removed), recordStrings(added)
+	}
+	if r != nil {
+		e.Rcode = dns.RcodeToString[r.Rcode]
+	}
+	if err != nil {
+		e.Result = err.Error()
+	}
+
+	if err := appendAudit(&e); err != nil {
+		log.Printf("Unable to write audit log: %s", err)
+	}
+}
+
+func appendAudit(e *auditEntry) error {
+	data, err := json.Marshal(e)
+	if err != nil {
+		return err
+	}
+	data = append(data, '\n')
+
+	file := auditFile()
+	if err := os.MkdirAll(filepath.Dir(file), 0700); err != nil {
+		return err
+	}
+	if err := rotateAudit(file, len(data)); err != nil {
+		return err
+	}
+
+	f, err := os.OpenFile(file, os.O_WRONLY|os.O_APPEND|os.O_CREATE, 0600)
+	if err != nil {
+		return err
+	}
+	defer f.Close()
+
+	_, err = f.Write(data)
+	return err
+}
+
+// rotateAudit renames file to file.1 (and file.1 to file.2 and so on,
+// keeping audit.keep old files) if adding size bytes would take it over
+// audit.max-size megabytes. A max-size of 0 turns rotation off.
+func rotateAudit(file string, size int) error {
+	max := viper.GetInt64("audit.max-size") * 1024 * 1024
+	if max <= 0 {
+		return nil
+	}
+	fi, err := os.Stat(file)
+	if os.IsNotExist(err) {
+		return nil
+	} else if err != nil {
+		return err
+	}
+	if fi.Size()+int64(size) <= max {
+		return nil
+	}
+
+	keep := viper.GetInt("audit.keep")
+	if keep <= 0 {
+		return fmt.Errorf("%s has reached audit.max-size and audit.keep is %d", file, keep)
+	}
+	os.Remove(fmt.Sprintf("%s.%d", file, keep))
+	for i := keep - 1; i > 0; i-- {
+		os.Rename(fmt.Sprintf("%s.%d", file, i), fmt.Sprintf("%s.%d", file, i+1))
+	}
+	return os.Rename(file, file+".1")
+}
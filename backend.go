@@ -0,0 +1,53 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"github.com/miekg/dns"
+)
+
+// A Backend carries out the DNS operations of the library: the queries
+// (for zone discovery and looking up records) and the UPDATE messages
+// built from the operations. The default sends them to the configured
+// servers; another Backend can answer them from memory (e.g. in tests) or
+// translate them into the API calls of a DNS provider.
+//
+// The messages to send carry the TSIG record they are signed with, but
+// without the signature, which is only computed when sending them over DNS.
+type Backend interface {
+	Exchange(m *dns.Msg, server string) (*dns.Msg, error)
+}
+
+// A Backend that is also a Transferer is used for zone transfers too (by
+// the commands working on whole zones); otherwise zones are transferred
+// from the server. Transfer returns the records of the zone, starting and
+// ending with its SOA record, as in an AXFR.
+type Transferer interface {
+	Transfer(zone, server string) ([]dns.RR, error)
+}
+
+// Set with Updater.SetBackend; nil for the default of sending the messages
+// over DNS.
+var dnsBackend Backend
+
+// SetBackend makes the updater use b for its DNS operations, or the
+// configured servers again if b is nil.
+func (u *Updater) SetBackend(b Backend) {
+	apiLock.Lock()
+	defer apiLock.Unlock()
+	dnsBackend = b
+	zoneCache = make(map[string]string)
+}
@@ -0,0 +1,264 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"context"
+	"errors"
+	"strings"
+	"testing"
+
+	"github.com/miekg/dns"
+	"github.com/spf13/viper"
+)
+
+// testConfig resets the settings to the defaults, with a server and key
+// that are never contacted and without the journal, history or the
+// skipping of unchanged updates, and applies settings on top.
+func testConfig(t *testing.T, settings map[string]interface{}) {
+	t.Helper()
+	viper.Reset()
+	setDefaults()
+	viper.Set("server", "192.0.2.53:53")
+	viper.Set("tsig-name", "test-key.")
+	viper.Set("tsig-secret", "c2VjcmV0")
+	viper.Set("journal", false)
+	viper.Set("history", false)
+	viper.Set("skip-unchanged", false)
+	viper.Set("retry.attempts", 1)
+	for key, value := range settings {
+		viper.Set(key, value)
+	}
+
+	overridden = make(map[string]bool)
+	zoneCache = make(map[string]string)
+	readZoneRoutes()
+	readReverseZones()
+	t.Cleanup(func() {
+		dnsBackend = nil
+		viper.Reset()
+	})
+}
+
+func mustRR(t *testing.T, s string) dns.RR {
+	t.Helper()
+	rr, err := dns.NewRR(s)
+	if err != nil || rr == nil {
+		t.Fatalf("invalid test record %q: %v", s, err)
+	}
+	return rr
+}
+
+// formatOps returns the operations as strings, for comparing and printing
+// them.
+func formatOps(ops []operation) []string {
+	var res []string
+	for _, op := range ops {
+		res = append(res, op.action+" "+op.rr.String())
+	}
+	return res
+}
+
+func checkOps(t *testing.T, got, want []operation) {
+	t.Helper()
+	g, w := formatOps(got), formatOps(want)
+	if strings.Join(g, "\n") != strings.Join(w, "\n") {
+		t.Errorf("got operations\n\t%s\nwant\n\t%s", strings.Join(g, "\n\t"), strings.Join(w, "\n\t"))
+	}
+}
+
+// A mockBackend answers queries from a fixed set of records, and records
+// the UPDATE messages sent to it, refusing those for the zones in refuse.
+// With minimalAny, ANY queries are answered with the last RRset only, as
+// BIND's minimal-any does.
+type mockBackend struct {
+	records    []dns.RR
+	refuse     map[string]bool
+	updates    []*dns.Msg
+	minimalAny bool
+}
+
+func (b *mockBackend) Exchange(m *dns.Msg, server string) (*dns.Msg, error) {
+	r := new(dns.Msg)
+	r.SetReply(m)
+	if m.Opcode == dns.OpcodeUpdate {
+		b.updates = append(b.updates, m.Copy())
+		if b.refuse[msgName(m)] {
+			r.Rcode = dns.RcodeRefused
+		}
+		return r, nil
+	}
+
+	q := m.Question[0]
+	for _, rr := range b.records {
+		if strings.EqualFold(rr.Header().Name, q.Name) &&
+			(q.Qtype == dns.TypeANY || q.Qtype == rr.Header().Rrtype) {
+			r.Answer = append(r.Answer, rr)
+		}
+	}
+	if b.minimalAny && q.Qtype == dns.TypeANY && len(r.Answer) > 0 {
+		last := r.Answer[len(r.Answer)-1].Header().Rrtype
+		var rrset []dns.RR
+		for _, rr := range r.Answer {
+			if rr.Header().Rrtype == last {
+				rrset = append(rrset, rr)
+			}
+		}
+		r.Answer = rrset
+	}
+	return r, nil
+}
+
+func useMockBackend(t *testing.T, settings map[string]interface{}, records ...string) *mockBackend {
+	t.Helper()
+	testConfig(t, settings)
+	b := &mockBackend{refuse: make(map[string]bool)}
+	for _, record := range records {
+		b.records = append(b.records, mustRR(t, record))
+	}
+	dnsBackend = b
+	return b
+}
+
+func TestSendUpdate(t *testing.T) {
+	b := useMockBackend(t, nil)
+	ops := []operation{{"replace", mustRR(t, "www.example.org. 300 IN A 192.0.2.1")}}
+
+	r, err := sendUpdate("example.org.", ops)
+	if err != nil {
+		t.Fatalf("sendUpdate: %v", err)
+	}
+	if r.Rcode != dns.RcodeSuccess {
+		t.Errorf("got rcode %s", dns.RcodeToString[r.Rcode])
+	}
+	if len(b.updates) != 1 {
+		t.Fatalf("got %d updates, want 1", len(b.updates))
+	}
+
+	m := b.updates[0]
+	if msgName(m) != "example.org." {
+		t.Errorf("update for zone %s, want example.org.", msgName(m))
+	}
+	if tsig := m.IsTsig(); tsig == nil || tsig.Hdr.Name != "test-key." {
+		t.Errorf("update not signed with test-key.: %v", tsig)
+	}
+	if len(m.Ns) != 2 || m.Ns[0].Header().Class != dns.ClassANY ||
+		m.Ns[1].String() != ops[0].rr.String() {
+		t.Errorf("update doesn't replace the RRset:\n%v", m.Ns)
+	}
+}
+
+func TestSendUpdateUnchanged(t *testing.T) {
+	b := useMockBackend(t, map[string]interface{}{"skip-unchanged": true},
+		"www.example.org. 300 IN A 192.0.2.1")
+	ops := []operation{{"replace", mustRR(t, "www.example.org. 300 IN A 192.0.2.1")}}
+
+	skipped := updatesSkipped
+	if _, err := sendUpdate("example.org.", ops); err != nil {
+		t.Fatalf("sendUpdate: %v", err)
+	}
+	if len(b.updates) != 0 {
+		t.Errorf("sent %d updates that change nothing", len(b.updates))
+	}
+	if updatesSkipped != skipped+1 {
+		t.Errorf("update not counted as skipped")
+	}
+
+	// A prerequisite has to be checked by the server
+	ops = append([]operation{{"yxrr", mustRR(t, "www.example.org. 300 IN A 192.0.2.1")}}, ops...)
+	if _, err := sendUpdate("example.org.", ops); err != nil {
+		t.Fatalf("sendUpdate: %v", err)
+	}
+	if len(b.updates) != 1 {
+		t.Errorf("update with prerequisites not sent")
+	}
+}
+
+func TestSendUpdateRefused(t *testing.T) {
+	b := useMockBackend(t, nil)
+	b.refuse["example.org."] = true
+	ops := []operation{{"append", mustRR(t, "www.example.org. 300 IN A 192.0.2.1")}}
+
+	_, err := sendUpdate("example.org.", ops)
+	var rerr *rcodeError
+	if !errors.As(err, &rerr) || rerr.rcode != dns.RcodeRefused {
+		t.Errorf("got error %v, want REFUSED", err)
+	}
+}
+
+func TestSendTransactionRollback(t *testing.T) {
+	b := useMockBackend(t, nil)
+	b.refuse["example.net."] = true
+	updates := []*zoneUpdate{
+		{zone: "example.org.", ops: []operation{{"append", mustRR(t, "www.example.org. 300 IN A 192.0.2.1")}}},
+		{zone: "example.net.", ops: []operation{{"append", mustRR(t, "www.example.net. 300 IN A 192.0.2.1")}}},
+	}
+
+	err := sendTransaction(updates)
+	if err == nil || !strings.Contains(err.Error(), "rolled back") {
+		t.Fatalf("got error %v, want a rolled back transaction", err)
+	}
+	if !updates[0].sent || updates[1].sent || updates[1].err == nil {
+		t.Errorf("wrong results: sent %t/%t, errors %v/%v",
+			updates[0].sent, updates[1].sent, updates[0].err, updates[1].err)
+	}
+
+	// The update of example.org is undone by deleting the RRset it created
+	if len(b.updates) != 3 {
+		t.Fatalf("got %d updates, want 3", len(b.updates))
+	}
+	undo := b.updates[2]
+	if msgName(undo) != "example.org." || len(undo.Ns) != 1 ||
+		undo.Ns[0].Header().Class != dns.ClassANY || undo.Ns[0].Header().Rrtype != dns.TypeA {
+		t.Errorf("wrong rollback update:\n%v", undo)
+	}
+}
+
+// A cancellingBackend cancels the updates once the first update is sent,
+// as stop does on a signal.
+type cancellingBackend struct {
+	*mockBackend
+	cancel func()
+}
+
+func (b *cancellingBackend) Exchange(m *dns.Msg, server string) (*dns.Msg, error) {
+	r, err := b.mockBackend.Exchange(m, server)
+	if m.Opcode == dns.OpcodeUpdate {
+		b.cancel()
+	}
+	return r, err
+}
+
+func TestSendTransactionCancelled(t *testing.T) {
+	b := useMockBackend(t, nil)
+	ctx, cancel := context.WithCancel(context.Background())
+	cancelCtx = ctx
+	defer func() { cancelCtx = context.Background() }()
+	dnsBackend = &cancellingBackend{b, cancel}
+	updates := []*zoneUpdate{
+		{zone: "example.org.", ops: []operation{{"append", mustRR(t, "www.example.org. 300 IN A 192.0.2.1")}}},
+		{zone: "example.net.", ops: []operation{{"append", mustRR(t, "www.example.net. 300 IN A 192.0.2.1")}}},
+	}
+
+	err := sendTransaction(updates)
+	if Kind(err) != ErrCancelled || !strings.Contains(err.Error(), "rolled back") {
+		t.Fatalf("got error %v, want a cancelled and rolled back transaction", err)
+	}
+	// The rollback of example.org is still sent
+	if len(b.updates) != 2 || msgName(b.updates[1]) != "example.org." {
+		t.Errorf("got %d updates, want the update and rollback of example.org.", len(b.updates))
+	}
+}
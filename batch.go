@@ -0,0 +1,250 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"bufio"
+	"fmt"
+	"io"
+	"log"
+	"os"
+	"strings"
+)
+
+// The operations accepted in batch files. "add" is an alias for "append",
+// mirroring the --add flag.
+var batchActions = map[string]string{
+	"add":     "append",
+	"append":  "append",
+	"replace": "replace",
+	"delete":  "delete",
+}
+
+// A zoneUpdate holds the operations to be sent to a single zone, and, once
+// sendUpdates has tried to send them, whether that succeeded.
+type zoneUpdate struct {
+	zone string
+	ops  []operation
+	sent bool
+	err  error
+}
+
+// A batchGroup holds consecutive operations of a batch file. The
+// operations between "begin" and "commit" lines form a transaction group,
+// which is sent with sendTransaction.
+type batchGroup struct {
+	ops         []operation
+	transaction bool
+}
+
+// readBatch parses a batch file, ignoring transaction groups.
+func readBatch(r io.Reader) ([]operation, error) {
+	groups, err := readBatchGroups(r)
+	var ops []operation
+	for _, g := range groups {
+		ops = append(ops, g.ops...)
+	}
+	return ops, err
+}
+
+// readBatchGroups parses a batch file: one operation per line, consisting
+// of an action followed by a record in zone file format, or "begin" or
+// "commit" around the operations of a transaction group. Empty lines and
+// lines starting with '#' are ignored.
+func readBatchGroups(r io.Reader) ([]*batchGroup, error) {
+	var groups []*batchGroup
+	var cur *batchGroup
+	scanner := bufio.NewScanner(r)
+	lineno := 0
+	for scanner.Scan() {
+		lineno++
+		line := strings.TrimSpace(scanner.Text())
+		if len(line) == 0 || strings.HasPrefix(line, "#") {
+			continue
+		}
+
+		switch strings.ToLower(line) {
+		case "begin":
+			if cur != nil && cur.transaction {
+				return nil, fmt.Errorf("line %d: nested 'begin'", lineno)
+			}
+			cur = &batchGroup{transaction: true}
+			groups = append(groups, cur)
+			continue
+		case "commit":
+			if cur == nil || !cur.transaction {
+				return nil, fmt.Errorf("line %d: 'commit' without 'begin'", lineno)
+			}
+			cur = nil
+			continue
+		}
+
+		fields := strings.Fields(line)
+		action, ok := batchActions[strings.ToLower(fields[0])]
+		if !ok || len(fields) < 2 {
+			return nil, fmt.Errorf("line %d: expected '<action> <record>'", lineno)
+		}
+
+		record := strings.TrimSpace(line[len(fields[0]):])
+		rr, err := parseRecord(record, action)
+		if err != nil {
+			return nil, fmt.Errorf("line %d: %s", lineno, err)
+		}
+		if cur == nil {
+			cur = &batchGroup{}
+			groups = append(groups, cur)
+		}
+		cur.ops = append(cur.ops, operation{action, rr})
+	}
+	if cur != nil && cur.transaction {
+		return nil, fmt.Errorf("missing 'commit' at end of file")
+	}
+	return groups, scanner.Err()
+}
+
+// groupByZone splits ops into one update per zone, keeping the order in
+// which zones and operations first appear.
+func groupByZone(ops []operation) []*zoneUpdate {
+	var updates []*zoneUpdate
+	byZone := make(map[string]*zoneUpdate)
+	for _, op := range ops {
+		zone := getZone(op.rr.Header().Name)
+		u, ok := byZone[zone]
+		if !ok {
+			u = &zoneUpdate{zone: zone}
+			byZone[zone] = u
+			updates = append(updates, u)
+		}
+		u.ops = append(u.ops, op)
+	}
+	return updates
+}
+
+func batch(args []string) int {
+	if len(args) != 1 {
+		log.Print("Usage: update-dns batch <file>")
+		return 1
+	}
+
+	var r io.Reader = os.Stdin
+	if args[0] != "-" {
+		f, err := os.Open(args[0])
+		if err != nil {
+			log.Printf("Unable to open batch file: %s", err)
+			return 1
+		}
+		defer f.Close()
+		r = f
+	}
+
+	groups, err := readBatchGroups(r)
+	if err != nil {
+		log.Printf("Unable to parse batch file: %s", err)
+		return 1
+	}
+
+	exitcode := 0
+	for _, g := range groups {
+		var code int
+		if g.transaction {
+			code = batchTransaction(g.ops)
+		} else {
+			code = batchUpdate(g.ops)
+		}
+		if code != 0 {
+			exitcode = code
+		}
+	}
+	return exitcode
+}
+
+// batchTransaction sends the operations of a transaction group, which are
+// either all applied or (as far as possible) not at all.
+func batchTransaction(ops []operation) int {
+	updates := groupByZone(ops)
+	for _, u := range updates {
+		if err := checkGuards(u.zone, u.ops, false); err != nil {
+			log.Printf("Not sending transaction: zone %s: %s", u.zone, err)
+			return 1
+		}
+	}
+	if err := sendTransaction(updates); err != nil {
+		log.Printf("Transaction failed: %s", err)
+		return exitCode(err)
+	}
+	infof("Committed transaction (%d operations in %d zones)", len(ops), len(updates))
+
+	exitcode := 0
+	for _, u := range updates {
+		if err := waitSecondaries(u.zone); err != nil {
+			log.Printf("Secondaries of %s haven't caught up: %s", u.zone, err)
+			exitcode = exitNetwork
+		}
+	}
+	return exitcode
+}
+
+// sendBatch sends ops, merged into one update per zone, continuing with the
+// other zones if one fails. The result of each update is recorded in it;
+// updates refused by the guards fail with an ErrValidation error.
+func sendBatch(ops []operation) []*zoneUpdate {
+	p := newProgress("Batch", "operations", len(ops))
+	defer p.finish()
+
+	updates := groupByZone(ops)
+	for _, u := range updates {
+		sendZoneUpdate(u)
+		p.add(len(u.ops))
+	}
+	return updates
+}
+
+// sendZoneUpdate checks u against the guards and sends it, recording the
+// result in u. Config errors (e.g. a key that can't be read) only fail u,
+// not the whole batch.
+func sendZoneUpdate(u *zoneUpdate) {
+	defer recoverError(&u.err)
+	if err := checkGuards(u.zone, u.ops, false); err != nil {
+		u.err = kindError(ErrValidation, err)
+	} else if u.err = update(u.zone, u.ops); u.err == nil {
+		u.sent = true
+	}
+}
+
+// batchUpdate sends the operations outside transaction groups with
+// sendBatch, and waits for the secondaries of the zones updated.
+func batchUpdate(ops []operation) int {
+	exitcode := 0
+	for _, u := range sendBatch(ops) {
+		switch {
+		case Kind(u.err) == ErrValidation:
+			log.Printf("Not updating zone %s: %s", u.zone, u.err)
+			exitcode = 1
+			continue
+		case u.err != nil:
+			log.Printf("Unable to update zone %s: %s", u.zone, u.err)
+			exitcode = exitCode(u.err)
+			continue
+		}
+		infof("Updated zone %s (%d operations)", u.zone, len(u.ops))
+
+		if err := waitSecondaries(u.zone); err != nil {
+			log.Printf("Secondaries of %s haven't caught up: %s", u.zone, err)
+			exitcode = exitNetwork
+		}
+	}
+	return exitcode
+}
@@ -0,0 +1,81 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"strings"
+	"testing"
+)
+
+func TestReadBatchGroups(t *testing.T) {
+	testConfig(t, nil)
+	input := `
+# A comment
+add www.example.org. 300 A 192.0.2.1
+
+begin
+replace mail.example.org. 300 MX 10 mx.example.org.
+DELETE old.example.org.
+commit
+append www.example.org. 300 AAAA 2001:db8::1
+`
+	groups, err := readBatchGroups(strings.NewReader(input))
+	if err != nil {
+		t.Fatalf("readBatchGroups: %v", err)
+	}
+
+	want := []struct {
+		transaction bool
+		ops         []string
+	}{
+		{false, []string{"append www.example.org./A"}},
+		{true, []string{"replace mail.example.org./MX", "delete old.example.org./ANY"}},
+		{false, []string{"append www.example.org./AAAA"}},
+	}
+	if len(groups) != len(want) {
+		t.Fatalf("got %d groups, want %d", len(groups), len(want))
+	}
+	for i, g := range groups {
+		var ops []string
+		for _, op := range g.ops {
+			ops = append(ops, op.action+" "+rrsetKey(op.rr))
+		}
+		if g.transaction != want[i].transaction || strings.Join(ops, ", ") != strings.Join(want[i].ops, ", ") {
+			t.Errorf("group %d: got %t %q, want %t %q", i, g.transaction, ops, want[i].transaction, want[i].ops)
+		}
+	}
+}
+
+func TestReadBatchGroupsErrors(t *testing.T) {
+	testConfig(t, nil)
+	tests := []struct {
+		input string
+		err   string
+	}{
+		{"begin\nbegin\ncommit\n", "line 2: nested 'begin'"},
+		{"commit\n", "line 1: 'commit' without 'begin'"},
+		{"begin\nadd www.example.org. 300 A 192.0.2.1\n", "missing 'commit' at end of file"},
+		{"frobnicate www.example.org. 300 A 192.0.2.1\n", "line 1: expected '<action> <record>'"},
+		{"\nadd\n", "line 2: expected '<action> <record>'"},
+		{"add www.example.org. 300 A 192.0.2.256\n", "line 1: "},
+	}
+	for _, test := range tests {
+		_, err := readBatchGroups(strings.NewReader(test.input))
+		if err == nil || !strings.HasPrefix(err.Error(), test.err) {
+			t.Errorf("readBatchGroups(%q): got error %v, want %q", test.input, err, test.err)
+		}
+	}
+}
@@ -0,0 +1,87 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"fmt"
+	"os"
+	"regexp"
+	"strings"
+
+	"github.com/miekg/dns"
+)
+
+// A bindKey is a TSIG key from a BIND key file (as written by tsig-keygen
+// or ddns-confgen).
+type bindKey struct {
+	name      string
+	algorithm string
+	secret    string
+}
+
+var (
+	bindComment   = regexp.MustCompile(`(?s)"[^"]*"|/\*.*?\*/|//[^\n]*|#[^\n]*`)
+	bindKeyStmt   = regexp.MustCompile(`key\s+"?([^"\s{]+)"?\s*\{([^}]*)\}\s*;`)
+	bindAlgorithm = regexp.MustCompile(`algorithm\s+"?([^";\s]+)"?\s*;`)
+	bindSecret    = regexp.MustCompile(`secret\s+"([^"]+)"\s*;`)
+)
+
+// parseBindKeys parses the key statements in the contents of a BIND
+// configuration or key file:
+//
+//	key "ddns-key" {
+//		algorithm hmac-sha256;
+//		secret "...";
+//	};
+func parseBindKeys(data string) ([]bindKey, error) {
+	// Secrets can contain "//", so leave quoted strings alone
+	data = bindComment.ReplaceAllStringFunc(data, func(s string) string {
+		if strings.HasPrefix(s, `"`) {
+			return s
+		}
+		return ""
+	})
+
+	var keys []bindKey
+	for _, m := range bindKeyStmt.FindAllStringSubmatch(data, -1) {
+		alg := bindAlgorithm.FindStringSubmatch(m[2])
+		secret := bindSecret.FindStringSubmatch(m[2])
+		if alg == nil || secret == nil {
+			return nil, fmt.Errorf("key %s: missing algorithm or secret", m[1])
+		}
+		if _, ok := lookupTsigAlgorithm(alg[1]); !ok {
+			return nil, fmt.Errorf("key %s: unknown algorithm %s", m[1], alg[1])
+		}
+		keys = append(keys, bindKey{dns.Fqdn(m[1]), alg[1], secret[1]})
+	}
+
+	if len(keys) == 0 {
+		return nil, fmt.Errorf("no key statements found")
+	}
+	return keys, nil
+}
+
+func readBindKeys(file string) ([]bindKey, error) {
+	data, err := os.ReadFile(file)
+	if err != nil {
+		return nil, err
+	}
+	keys, err := parseBindKeys(string(data))
+	if err != nil {
+		return nil, fmt.Errorf("%s: %s", file, err)
+	}
+	return keys, nil
+}
@@ -0,0 +1,63 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"reflect"
+	"testing"
+)
+
+func TestParseBindKeys(t *testing.T) {
+	data := `
+// The keys for the dynamic zones
+key "ddns-key" {
+	algorithm hmac-sha256;
+	secret "c2VjcmV0Ly9ub3QtYS1jb21tZW50"; # a comment
+};
+
+/* An older key */
+key legacy.example.org {
+	algorithm "HMAC-MD5.SIG-ALG.REG.INT";
+	secret "b2xkLy9zZWNyZXQ=";
+};
+`
+	keys, err := parseBindKeys(data)
+	if err != nil {
+		t.Fatalf("parseBindKeys: %v", err)
+	}
+	want := []bindKey{
+		{"ddns-key.", "hmac-sha256", "c2VjcmV0Ly9ub3QtYS1jb21tZW50"},
+		{"legacy.example.org.", "HMAC-MD5.SIG-ALG.REG.INT", "b2xkLy9zZWNyZXQ="},
+	}
+	if !reflect.DeepEqual(keys, want) {
+		t.Errorf("got keys %+v, want %+v", keys, want)
+	}
+}
+
+func TestParseBindKeysErrors(t *testing.T) {
+	tests := []string{
+		"",
+		"# key \"commented\" { algorithm hmac-sha256; secret \"c2VjcmV0\"; };",
+		"key \"ddns-key\" { algorithm hmac-sha256; };",
+		"key \"ddns-key\" { secret \"c2VjcmV0\"; };",
+		"key \"ddns-key\" { algorithm hmac-foo; secret \"c2VjcmV0\"; };",
+	}
+	for _, data := range tests {
+		if keys, err := parseBindKeys(data); err == nil {
+			t.Errorf("parseBindKeys(%q) = %+v, want an error", data, keys)
+		}
+	}
+}
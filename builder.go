@@ -0,0 +1,272 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"fmt"
+	"net"
+	"strconv"
+	"strings"
+
+	"github.com/miekg/dns"
+)
+
+// An UpdateBuilder describes the change of the records at a single name,
+// e.g.:
+//
+//	b := updatedns.Replace("host.example.com").A("10.0.0.5").TTL(300).WithReverse()
+//	err := updater.Send(b)
+//
+// The values are checked as they are added; the first error is kept and
+// returned by Messages and Updater.Send.
+type UpdateBuilder struct {
+	action  string
+	name    string
+	ttl     string
+	types   []string
+	records []string
+	lines   []string // whole records, as given on the command line
+	reverse bool
+	err     error
+}
+
+func newBuilder(action, name string) *UpdateBuilder {
+	b := &UpdateBuilder{action: action, name: name}
+	if _, ok := dns.IsDomainName(name); !ok || len(name) == 0 {
+		b.fail("invalid name: %s", name)
+	}
+	return b
+}
+
+// Replace starts an update replacing the RRsets of the records added with
+// those records.
+func Replace(name string) *UpdateBuilder {
+	return newBuilder("replace", name)
+}
+
+// Append starts an update adding records to their RRsets.
+func Append(name string) *UpdateBuilder {
+	return newBuilder("append", name)
+}
+
+// Remove starts an update removing single records from their RRsets.
+func Remove(name string) *UpdateBuilder {
+	return newBuilder("remove", name)
+}
+
+// Delete starts an update deleting the RRsets of the types given with
+// Type, or all the records at the name if no type is given.
+func Delete(name string) *UpdateBuilder {
+	return newBuilder("delete", name)
+}
+
+// recordBuilder returns a builder for records given as on the command line,
+// e.g. "host.example.com 300 A 10.0.0.5", which can be at different names.
+func recordBuilder(action string, records ...string) *UpdateBuilder {
+	return &UpdateBuilder{action: action, lines: records}
+}
+
+func (b *UpdateBuilder) fail(format string, args ...interface{}) *UpdateBuilder {
+	if b.err == nil {
+		b.err = kindError(ErrValidation, fmt.Errorf(format, args...))
+	}
+	return b
+}
+
+// TTL sets the TTL of the records (by default the configured ttl, or the
+// dns library's default).
+func (b *UpdateBuilder) TTL(ttl uint32) *UpdateBuilder {
+	if ttl > 1<<31-1 {
+		return b.fail("invalid TTL: %d", ttl)
+	}
+	b.ttl = strconv.FormatUint(uint64(ttl), 10)
+	return b
+}
+
+// Record adds a record of type rrtype with data in presentation format,
+// e.g. Record("MX", "10 mail.example.com").
+func (b *UpdateBuilder) Record(rrtype, data string) *UpdateBuilder {
+	if _, ok := dns.StringToType[strings.ToUpper(rrtype)]; !ok {
+		return b.fail("unknown record type: %s", rrtype)
+	}
+	if b.action == "delete" {
+		return b.fail("records can't be given when deleting; use Type")
+	}
+	b.records = append(b.records, strings.ToUpper(rrtype)+" "+data)
+	return b
+}
+
+// Type adds a type of RRset to delete.
+func (b *UpdateBuilder) Type(rrtype string) *UpdateBuilder {
+	if _, ok := dns.StringToType[strings.ToUpper(rrtype)]; !ok {
+		return b.fail("unknown record type: %s", rrtype)
+	}
+	if b.action != "delete" {
+		return b.fail("Type is only for deleting RRsets; use Record")
+	}
+	b.types = append(b.types, strings.ToUpper(rrtype))
+	return b
+}
+
+// A adds an A record with the IPv4 address ip.
+func (b *UpdateBuilder) A(ip string) *UpdateBuilder {
+	if addr := net.ParseIP(ip); addr == nil || addr.To4() == nil {
+		return b.fail("invalid IPv4 address: %s", ip)
+	}
+	return b.Record("A", ip)
+}
+
+// AAAA adds an AAAA record with the IPv6 address ip.
+func (b *UpdateBuilder) AAAA(ip string) *UpdateBuilder {
+	if addr := net.ParseIP(ip); addr == nil || addr.To4() != nil {
+		return b.fail("invalid IPv6 address: %s", ip)
+	}
+	return b.Record("AAAA", ip)
+}
+
+// CNAME adds a CNAME record pointing at target.
+func (b *UpdateBuilder) CNAME(target string) *UpdateBuilder {
+	if _, ok := dns.IsDomainName(target); !ok {
+		return b.fail("invalid CNAME target: %s", target)
+	}
+	return b.Record("CNAME", target)
+}
+
+// MX adds an MX record for the mail server host.
+func (b *UpdateBuilder) MX(preference uint16, host string) *UpdateBuilder {
+	if _, ok := dns.IsDomainName(host); !ok {
+		return b.fail("invalid MX host: %s", host)
+	}
+	return b.Record("MX", fmt.Sprintf("%d %s", preference, host))
+}
+
+// TXT adds a TXT record with the strings in text.
+func (b *UpdateBuilder) TXT(text ...string) *UpdateBuilder {
+	quoted := make([]string, 0, len(text))
+	for _, s := range text {
+		quoted = append(quoted, strconv.Quote(s))
+	}
+	return b.Record("TXT", strings.Join(quoted, " "))
+}
+
+// WithReverse also updates the PTR records of the A and AAAA records, as
+// with the reverse setting.
+func (b *UpdateBuilder) WithReverse() *UpdateBuilder {
+	b.reverse = true
+	return b
+}
+
+// operations returns the operations of the update, parsed as the records
+// given on the command line are.
+func (b *UpdateBuilder) operations() ([]operation, error) {
+	if b.err != nil {
+		return nil, b.err
+	}
+
+	var lines []string
+	switch {
+	case len(b.lines) > 0:
+		lines = b.lines
+	case b.action == "delete" && len(b.types) == 0:
+		lines = []string{b.name}
+	case b.action == "delete":
+		for _, t := range b.types {
+			lines = append(lines, b.name+" "+t)
+		}
+	case len(b.records) == 0:
+		return nil, kindError(ErrValidation, fmt.Errorf("no records given for %s", b.name))
+	default:
+		for _, r := range b.records {
+			lines = append(lines, strings.TrimSpace(b.name+" "+b.ttl)+" "+r)
+		}
+	}
+
+	var ops []operation
+	cnames := 0
+	for _, line := range lines {
+		rr, err := parseRecord(line, b.action)
+		if err != nil {
+			return nil, kindError(ErrValidation, fmt.Errorf("invalid record '%s': %s", line, err))
+		}
+		if rr.Header().Rrtype == dns.TypeCNAME {
+			cnames++
+		}
+		ops = append(ops, operation{b.action, rr})
+	}
+	// Conflicts between the records given on the command line are left to
+	// sendUpdate, which can be overridden with --force
+	if cnames > 0 && len(ops) > 1 && b.action != "delete" && len(b.lines) == 0 {
+		return nil, kindError(ErrValidation, fmt.Errorf("a CNAME can't be combined with other records at %s", b.name))
+	}
+	return ops, nil
+}
+
+// updates returns the updates to send for b, grouped by zone: the update
+// of the forward zone and, with WithReverse, those of the reverse zones.
+func (b *UpdateBuilder) updates() (forward, reverse []*zoneUpdate, err error) {
+	defer recoverError(&err)
+	ops, err := b.operations()
+	if err != nil {
+		return nil, nil, err
+	}
+	forward = groupByZone(ops)
+	if b.reverse {
+		reverse = reverseUpdates(ops)
+	}
+	return forward, reverse, nil
+}
+
+// Messages returns the (unsigned) UPDATE messages that would be sent for b,
+// one for each zone. The zones are discovered on the configured server, so
+// this needs an Updater to have been created.
+func (b *UpdateBuilder) Messages() ([]*dns.Msg, error) {
+	apiLock.Lock()
+	defer apiLock.Unlock()
+	forward, reverse, err := b.updates()
+	if err != nil {
+		return nil, err
+	}
+	var msgs []*dns.Msg
+	for _, u := range append(forward, reverse...) {
+		msgs = append(msgs, buildUpdate(u.zone, u.ops))
+	}
+	return msgs, nil
+}
+
+// Send sends the update described by b.
+func (u *Updater) Send(b *UpdateBuilder) (err error) {
+	apiLock.Lock()
+	defer apiLock.Unlock()
+	defer recoverError(&err)
+	ops, err := b.operations()
+	if err != nil {
+		return err
+	}
+	for _, f := range groupByZone(ops) {
+		if err := checkGuards(f.zone, f.ops, false); err != nil {
+			return kindError(ErrValidation, err)
+		}
+		if b.reverse {
+			err = updateWithReverse(f.zone, f.ops)
+		} else {
+			err = update(f.zone, f.ops)
+		}
+		if err != nil {
+			return err
+		}
+	}
+	return nil
+}
@@ -0,0 +1,244 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+// A C API for the updatedns package, built as a shared library with
+//
+//	go build -buildmode=c-shared -o libupdatedns.so ./capi
+//
+// which also writes libupdatedns.h. The functions return 0 on success, or
+// the exit status the command line tool would exit with; the message of
+// the last error is returned by update_dns_last_error.
+//
+// The library is meant to be used from one thread at a time. The calls can
+// be made from any thread (they are serialized), but there is only one
+// last error for the whole process: a program calling the library from
+// several threads has to hold a lock of its own from each call until it
+// has read the error.
+package main
+
+/*
+#include <stdlib.h>
+*/
+import "C"
+
+import (
+	"encoding/json"
+	"errors"
+	"strings"
+	"sync"
+	"unsafe"
+
+	"github.com/miekg/dns"
+	updatedns "github.com/tohojo/update-dns"
+)
+
+// The updater and the result of the last call are shared by all threads;
+// see the package comment.
+var (
+	lock       sync.Mutex
+	updater    *updatedns.Updater
+	lastError  string
+	lastKind   updatedns.ErrorKind
+	lastStatus int
+)
+
+func result(err error) C.int {
+	lock.Lock()
+	defer lock.Unlock()
+	lastError, lastKind = "", updatedns.ErrOther
+	if err != nil {
+		lastError, lastKind = err.Error(), updatedns.Kind(err)
+	}
+	lastStatus = updatedns.ExitCode(err)
+	return C.int(lastStatus)
+}
+
+func getUpdater() (*updatedns.Updater, error) {
+	lock.Lock()
+	defer lock.Unlock()
+	if updater == nil {
+		return nil, errors.New("update_dns_init hasn't been called")
+	}
+	return updater, nil
+}
+
+// update_dns_init reads the config file (or the default ones if file is
+// NULL or empty), optionally selecting a profile (which may be NULL).
+//
+//export update_dns_init
+func update_dns_init(file, profile *C.char) C.int {
+	config := map[string]interface{}{}
+	if profile != nil {
+		config["profile"] = C.GoString(profile)
+	}
+	return initUpdater(C.GoString(file), config)
+}
+
+// update_dns_init_json is like update_dns_init, with the settings in the
+// JSON object settings (which may be NULL) taking precedence over those in
+// the config file.
+//
+//export update_dns_init_json
+func update_dns_init_json(file, settings *C.char) C.int {
+	config := map[string]interface{}{}
+	if settings != nil {
+		if err := json.Unmarshal([]byte(C.GoString(settings)), &config); err != nil {
+			return result(&updatedns.Error{Kind: updatedns.ErrConfig, Err: err})
+		}
+	}
+	return initUpdater(C.GoString(file), config)
+}
+
+func initUpdater(file string, config map[string]interface{}) C.int {
+	u, err := updatedns.NewUpdaterFromFile(file, config)
+	if err == nil {
+		lock.Lock()
+		updater = u
+		lock.Unlock()
+	}
+	return result(err)
+}
+
+// update_dns_replace replaces the RRset of a record given as on the
+// command line, e.g. "test.example.org 300 A 127.0.0.1".
+//
+//export update_dns_replace
+func update_dns_replace(record *C.char) C.int {
+	u, err := getUpdater()
+	if err == nil {
+		err = u.Replace(C.GoString(record))
+	}
+	return result(err)
+}
+
+// update_dns_append adds a record to its RRset.
+//
+//export update_dns_append
+func update_dns_append(record *C.char) C.int {
+	u, err := getUpdater()
+	if err == nil {
+		err = u.Append(C.GoString(record))
+	}
+	return result(err)
+}
+
+// update_dns_delete deletes the RRset of type rrtype at name, or all the
+// records at name if rrtype is NULL or empty.
+//
+//export update_dns_delete
+func update_dns_delete(name, rrtype *C.char) C.int {
+	u, err := getUpdater()
+	if err == nil {
+		err = u.Delete(C.GoString(name), C.GoString(rrtype))
+	}
+	return result(err)
+}
+
+type jsonRecord struct {
+	Name  string `json:"name"`
+	TTL   uint32 `json:"ttl"`
+	Class string `json:"class"`
+	Type  string `json:"type"`
+	Data  string `json:"data"`
+}
+
+// update_dns_query returns the records of type rrtype (or all types, if
+// rrtype is NULL or "ANY") at name as a JSON array of objects with the
+// name, ttl, class, type and data of each record, or NULL on failure. The
+// string must be freed with update_dns_free.
+//
+//export update_dns_query
+func update_dns_query(name, rrtype *C.char) *C.char {
+	qtype := "ANY"
+	if rrtype != nil && len(C.GoString(rrtype)) > 0 {
+		qtype = C.GoString(rrtype)
+	}
+	u, err := getUpdater()
+	var rrs []dns.RR
+	if err == nil {
+		rrs, err = u.Lookup(C.GoString(name), qtype)
+	}
+	if result(err) != 0 {
+		return nil
+	}
+
+	records := []jsonRecord{}
+	for _, rr := range rrs {
+		hdr := rr.Header()
+		records = append(records, jsonRecord{
+			Name:  hdr.Name,
+			TTL:   hdr.Ttl,
+			Class: dns.ClassToString[hdr.Class],
+			Type:  dns.TypeToString[hdr.Rrtype],
+			Data:  strings.TrimPrefix(rr.String(), hdr.String()),
+		})
+	}
+	data, _ := json.Marshal(records)
+	return C.CString(string(data))
+}
+
+// update_dns_last_error_kind returns the kind of the error of the last
+// call (an updatedns.ErrorKind: 1 config, 2 resolution, 3 transport, 4
+// TSIG, 5 rcode, 6 validation, 7 partial, 8 cancelled, 0 other or none).
+//
+//export update_dns_last_error_kind
+func update_dns_last_error_kind() C.int {
+	lock.Lock()
+	defer lock.Unlock()
+	return C.int(lastKind)
+}
+
+// update_dns_last_status returns the status the last call returned (for
+// update_dns_query, which returns NULL on failure).
+//
+//export update_dns_last_status
+func update_dns_last_status() C.int {
+	lock.Lock()
+	defer lock.Unlock()
+	return C.int(lastStatus)
+}
+
+// update_dns_last_error returns the message of the error of the last call,
+// or NULL if it succeeded. The string must be freed with update_dns_free.
+//
+//export update_dns_last_error
+func update_dns_last_error() *C.char {
+	lock.Lock()
+	defer lock.Unlock()
+	if len(lastError) == 0 {
+		return nil
+	}
+	return C.CString(lastError)
+}
+
+// update_dns_free frees a string returned by the library.
+//
+//export update_dns_free
+func update_dns_free(s *C.char) {
+	C.free(unsafe.Pointer(s))
+}
+
+// update_dns_close releases the updater; update_dns_init has to be called
+// again before sending more updates.
+//
+//export update_dns_close
+func update_dns_close() {
+	lock.Lock()
+	defer lock.Unlock()
+	updater = nil
+}
+
+// Required for building a shared library
+func main() {}
@@ -0,0 +1,245 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"encoding/json"
+	"fmt"
+	"log"
+	"net"
+	"os"
+	"strings"
+
+	"github.com/miekg/dns"
+	"github.com/spf13/viper"
+)
+
+type problem struct {
+	Name    string `json:"name"`
+	Type    string `json:"type"`
+	Problem string `json:"problem"`
+	Detail  string `json:"detail"`
+}
+
+// zoneIndex maps the (lower case) names in a zone to their record types.
+type zoneIndex map[string]map[uint16]bool
+
+func indexZone(rrs []dns.RR) zoneIndex {
+	idx := make(zoneIndex)
+	for _, rr := range rrs {
+		name := strings.ToLower(rr.Header().Name)
+		if idx[name] == nil {
+			idx[name] = make(map[uint16]bool)
+		}
+		idx[name][rr.Header().Rrtype] = true
+	}
+	return idx
+}
+
+// recordTarget returns the name a CNAME, MX or SRV record points at.
+func recordTarget(rr dns.RR) string {
+	switch rr := rr.(type) {
+	case *dns.CNAME:
+		return rr.Target
+	case *dns.MX:
+		return rr.Mx
+	case *dns.SRV:
+		return rr.Target
+	}
+	return ""
+}
+
+// cnameConflicts checks that ops don't leave a CNAME next to other records
+// at any of the names they add records to.
+func cnameConflicts(ops []operation) error {
+	checked := make(map[string]bool)
+	for _, op := range ops {
+		name := strings.ToLower(op.rr.Header().Name)
+		if (op.action != "replace" && op.action != "append") || checked[name] {
+			continue
+		}
+		checked[name] = true
+
+		// The records are looked up by type, as the answer to an ANY
+		// query can leave RRsets out: all the types if a CNAME is added,
+		// and otherwise only whether there is a CNAME
+		types := []uint16{dns.TypeCNAME}
+		var nameOps []operation
+		for _, o := range ops {
+			if strings.EqualFold(o.rr.Header().Name, name) {
+				nameOps = append(nameOps, o)
+				if o.rr.Header().Rrtype == dns.TypeCNAME && (o.action == "replace" || o.action == "append") {
+					types = anyFallbackTypes
+				}
+			}
+		}
+		before, err := recordsOfTypes(name, types)
+		if err != nil {
+			return fmt.Errorf("unable to check %s for CNAME conflicts: %w", name, err)
+		}
+
+		var cname bool
+		var others []string
+		seen := make(map[uint16]bool)
+		for _, rr := range applyToRecords(before, nameOps) {
+			t := rr.Header().Rrtype
+			if t == dns.TypeCNAME {
+				cname = true
+			} else if !seen[t] {
+				others = append(others, dns.TypeToString[t])
+			}
+			seen[t] = true
+		}
+		if cname && len(others) > 0 {
+			return fmt.Errorf("%s would have both a CNAME and %s records (use --force to update anyway)",
+				name, strings.Join(others, ", "))
+		}
+	}
+	return nil
+}
+
+// resolves reports whether name has address records, on the configured
+// server or, for names it doesn't serve, using the system resolver.
+func resolves(name string) bool {
+	for _, rrtype := range []uint16{dns.TypeA, dns.TypeAAAA} {
+		if rrs, err := lookup(name, rrtype); err == nil {
+			for _, rr := range rrs {
+				if recordAddress(rr) != nil {
+					return true
+				}
+			}
+		}
+	}
+	addrs, err := net.LookupHost(strings.TrimSuffix(name, "."))
+	return err == nil && len(addrs) > 0
+}
+
+// checkTargets checks that the targets of the CNAME, MX, SRV and NS records
+// added by ops resolve to an address, or get one in the same update.
+func checkTargets(ops []operation) error {
+	added := make(map[string]bool)
+	for _, op := range ops {
+		if (op.action == "replace" || op.action == "append") && recordAddress(op.rr) != nil {
+			added[strings.ToLower(op.rr.Header().Name)] = true
+		}
+	}
+
+	for _, op := range ops {
+		if op.action != "replace" && op.action != "append" {
+			continue
+		}
+		target := recordTarget(op.rr)
+		if ns, ok := op.rr.(*dns.NS); ok {
+			target = ns.Ns
+		}
+		target = strings.ToLower(target)
+		if len(target) == 0 || target == "." || added[target] {
+			continue
+		}
+		if !resolves(target) {
+			return fmt.Errorf("the target %s of %s doesn't resolve to an address", target, op.rr.Header().Name)
+		}
+	}
+	return nil
+}
+
+func checkZone(zone string, rrs []dns.RR) []problem {
+	var problems []problem
+	report := func(rr dns.RR, kind, format string, args ...interface{}) {
+		problems = append(problems, problem{rr.Header().Name,
+			dns.TypeToString[rr.Header().Rrtype], kind, fmt.Sprintf(format, args...)})
+	}
+
+	idx := indexZone(rrs)
+	var seen []dns.RR
+	for _, rr := range rrs {
+		if !managedRecord(rr) {
+			continue
+		}
+		types := idx[strings.ToLower(rr.Header().Name)]
+
+		if rr.Header().Rrtype == dns.TypeCNAME {
+			for t := range types {
+				if t != dns.TypeCNAME && managedType(t) {
+					report(rr, "cname-conflict", "CNAME coexists with %s records", dns.TypeToString[t])
+				}
+			}
+		}
+
+		// Only targets inside the zone can be checked against the transfer
+		target := strings.ToLower(recordTarget(rr))
+		if len(target) > 0 && target != "." && dns.IsSubDomain(zone, target) {
+			ttypes := idx[target]
+			switch {
+			case rr.Header().Rrtype == dns.TypeCNAME && len(ttypes) == 0:
+				report(rr, "dangling-target", "%s does not exist", target)
+			case rr.Header().Rrtype != dns.TypeCNAME && !ttypes[dns.TypeA] &&
+				!ttypes[dns.TypeAAAA] && !ttypes[dns.TypeCNAME]:
+				report(rr, "dangling-target", "%s has no address records", target)
+			}
+		}
+
+		if ip := recordAddress(rr); ip != nil {
+			rev, _, _ := reverseName(ip)
+			ptrs, err := lookup(rev, dns.TypePTR)
+			if err != nil {
+				log.Printf("Unable to check PTR for %s: %s", ip, err)
+			} else if len(ptrs) == 0 {
+				report(rr, "missing-ptr", "no PTR record for %s", ip)
+			}
+		}
+
+		if findRecord(seen, rr) != nil {
+			report(rr, "duplicate", "%s", strings.TrimPrefix(rr.String(), rr.Header().String()))
+		}
+		seen = append(seen, rr)
+	}
+	return problems
+}
+
+func check(args []string) int {
+	if len(args) != 1 {
+		log.Print("Usage: update-dns check <zone>")
+		return 1
+	}
+	zone := strings.ToLower(dns.Fqdn(args[0]))
+
+	rrs, err := transfer(zone)
+	if err != nil {
+		log.Printf("Zone transfer failed: %s", err)
+		return 1
+	}
+
+	problems := checkZone(zone, rrs)
+	if viper.GetString("output") == "json" {
+		if problems == nil {
+			problems = []problem{}
+		}
+		enc := json.NewEncoder(os.Stdout)
+		enc.SetIndent("", "  ")
+		enc.Encode(problems)
+	} else {
+		for _, p := range problems {
+			fmt.Printf("%s\t%s\t%s\t%s\n", p.Name, p.Type, p.Problem, p.Detail)
+		}
+	}
+
+	if len(problems) > 0 {
+		infof("Found %d problems in %s", len(problems), zone)
+		return 1
+	}
+	return 0
+}
@@ -0,0 +1,48 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"strings"
+	"testing"
+
+	"github.com/miekg/dns"
+)
+
+// The CNAME check doesn't rely on the answers to ANY queries, which can
+// leave the CNAME out.
+func TestCNAMEConflictsMinimalAny(t *testing.T) {
+	b := useMockBackend(t, nil,
+		"alias.example.org. 300 IN CNAME www.example.org.",
+		"alias.example.org. 300 IN TXT \"note\"",
+		"www.example.org. 300 IN A 192.0.2.1")
+	b.minimalAny = true
+
+	err := cnameConflicts([]operation{{"append", mustRR(t, "alias.example.org. 300 IN A 192.0.2.2")}})
+	if err == nil || !strings.Contains(err.Error(), "both a CNAME and A") {
+		t.Errorf("got error %v, want a CNAME conflict", err)
+	}
+
+	// When adding a CNAME, every type is checked
+	cname := operation{"append", mustRR(t, "www.example.org. 300 IN CNAME alias.example.org.")}
+	if err := cnameConflicts([]operation{cname}); err == nil {
+		t.Error("adding a CNAME next to an A record succeeded")
+	}
+	deleteA := operation{"delete", &dns.A{Hdr: dns.RR_Header{Name: "www.example.org.", Rrtype: dns.TypeA}}}
+	if err := cnameConflicts([]operation{deleteA, cname}); err != nil {
+		t.Errorf("replacing the A record with a CNAME: %v", err)
+	}
+}
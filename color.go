@@ -0,0 +1,128 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"fmt"
+	"io"
+	"os"
+	"strings"
+	"unicode/utf8"
+
+	"github.com/miekg/dns"
+	"github.com/spf13/viper"
+	"golang.org/x/term"
+)
+
+// ANSI escape sequences for the colors used in the output
+const (
+	colorReset = "\033[0m"
+	colorBold  = "\033[1m"
+	colorDim   = "\033[2m"
+	colorRed   = "\033[31m"
+	colorGreen = "\033[32m"
+	colorCyan  = "\033[36m"
+)
+
+func isTerminal(w io.Writer) bool {
+	f, ok := w.(*os.File)
+	return ok && term.IsTerminal(int(f.Fd()))
+}
+
+// useColor reports whether output written to w should be colored: always
+// or never with --color, and otherwise only if w is a terminal and NO_COLOR
+// isn't set.
+func useColor(w io.Writer) bool {
+	switch viper.GetString("color") {
+	case "always":
+		return true
+	case "never":
+		return false
+	}
+	return isTerminal(w) && len(os.Getenv("NO_COLOR")) == 0
+}
+
+func colorize(on bool, color, s string) string {
+	if !on || len(s) == 0 {
+		return s
+	}
+	return color + s + colorReset
+}
+
+// pad pads s with spaces to width runes.
+func pad(s string, width int) string {
+	if n := utf8.RuneCountInString(s); n < width {
+		return s + strings.Repeat(" ", width-n)
+	}
+	return s
+}
+
+// formatRecords returns the records in rrs formatted for writing to w. On a
+// terminal (or with --color=always) the name, TTL, class and type are
+// aligned in columns and colored; otherwise the records are in the usual
+// tab-separated presentation format, so they can be parsed by scripts.
+func formatRecords(w io.Writer, rrs []dns.RR) []string {
+	color := useColor(w)
+	lines := make([]string, 0, len(rrs))
+	if !color && !isTerminal(w) {
+		for _, rr := range rrs {
+			lines = append(lines, toUnicode(rr.String()))
+		}
+		return lines
+	}
+
+	records := make([]jsonRecord, 0, len(rrs))
+	var nameWidth, ttlWidth, classWidth, typeWidth int
+	for _, rr := range rrs {
+		rec := toJSONRecord(rr)
+		records = append(records, rec)
+		nameWidth = maxInt(nameWidth, utf8.RuneCountInString(rec.Name))
+		ttlWidth = maxInt(ttlWidth, len(fmt.Sprint(rec.TTL)))
+		classWidth = maxInt(classWidth, len(rec.Class))
+		typeWidth = maxInt(typeWidth, len(rec.Type))
+	}
+	for _, rec := range records {
+		lines = append(lines, fmt.Sprintf("%s  %s  %s  %s  %s",
+			colorize(color, colorBold, pad(rec.Name, nameWidth)),
+			colorize(color, colorDim, fmt.Sprintf("%*d", ttlWidth, rec.TTL)),
+			colorize(color, colorDim, pad(rec.Class, classWidth)),
+			colorize(color, colorCyan, pad(rec.Type, typeWidth)),
+			rec.Data))
+	}
+	return lines
+}
+
+// formatChanges returns the records removed and added formatted for
+// writing to w like formatRecords, each with a (colored) - or + in front.
+func formatChanges(w io.Writer, removed, added []dns.RR) []string {
+	color := useColor(w)
+	lines := formatRecords(w, append(append([]dns.RR(nil), removed...), added...))
+	for i := range lines {
+		if i < len(removed) {
+			lines[i] = colorize(color, colorRed, "-") + lines[i]
+		} else {
+			lines[i] = colorize(color, colorGreen, "+") + lines[i]
+		}
+	}
+	return lines
+}
+
+func maxInt(a, b int) int {
+	if a > b {
+		return a
+	}
+	return b
+}
@@ -0,0 +1,172 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"fmt"
+	"io"
+	"log"
+	"os"
+	"strings"
+
+	"github.com/spf13/pflag"
+)
+
+// The subcommands; anything else on the command line is a record.
+var commandNames = []string{"acme", "apply", "batch", "check", "completions",
+	"config", "copy", "daemon", "ddns", "diff", "export", "history", "keygen", "list", "nsupdate", "prune",
+	"query", "rename", "serve", "shell", "undo", "verify", "wait"}
+
+// Flags taking a record type or output format, which get their own
+// completions.
+var typeFlags = []string{"-t", "--type"}
+var formatFlags = []string{"-o", "--output"}
+var profileFlags = []string{"-p", "--profile"}
+
+func flagNames() []string {
+	var names []string
+	commandFlags.VisitAll(func(f *pflag.Flag) {
+		names = append(names, "--"+f.Name)
+		if len(f.Shorthand) > 0 {
+			names = append(names, "-"+f.Shorthand)
+		}
+	})
+	return names
+}
+
+func bashCompletion(w io.Writer) {
+	fmt.Fprintf(w, `_update_dns() {
+    local cur="${COMP_WORDS[COMP_CWORD]}"
+    local prev="${COMP_WORDS[COMP_CWORD-1]}"
+    local types="%s"
+
+    case "$prev" in
+        %s)
+            COMPREPLY=($(compgen -W "$types" -- "${cur^^}"))
+            return;;
+        %s)
+            COMPREPLY=($(compgen -W "text json" -- "$cur"))
+            return;;
+        %s)
+            COMPREPLY=($(compgen -W "%s" -- "$cur"))
+            return;;
+    esac
+
+    if [[ "$cur" == -* ]]; then
+        COMPREPLY=($(compgen -W "%s" -- "$cur"))
+    elif [[ $COMP_CWORD -eq 1 ]]; then
+        COMPREPLY=($(compgen -W "%s" -- "$cur"))
+    elif [[ $COMP_CWORD -gt 2 ]]; then
+        COMPREPLY=($(compgen -W "$types" -- "${cur^^}"))
+    fi
+}
+complete -o default -F _update_dns update-dns
+`, strings.Join(recordTypes(), " "), strings.Join(typeFlags, "|"),
+		strings.Join(formatFlags, "|"), strings.Join(profileFlags, "|"),
+		strings.Join(profileNames(), " "), strings.Join(flagNames(), " "),
+		strings.Join(commandNames, " "))
+}
+
+// zshCompletion writes a script that works both sourced and installed as
+// _update-dns in $fpath, where the #compdef line registers it.
+func zshCompletion(w io.Writer) {
+	fmt.Fprintf(w, `#compdef update-dns
+
+_update_dns() {
+    local -a types
+    types=(%s)
+
+    case "$words[CURRENT-1]" in
+        %s)
+            compadd -a types
+            return;;
+        %s)
+            compadd text json
+            return;;
+        %s)
+            compadd -- %s
+            return;;
+    esac
+
+    if [[ "$words[CURRENT]" == -* ]]; then
+        compadd -- %s
+    elif (( CURRENT == 2 )); then
+        compadd %s
+    elif (( CURRENT > 3 )); then
+        compadd -a types
+    else
+        _files
+    fi
+}
+
+if [[ "$funcstack[1]" == "_update-dns" ]]; then
+    # Autoloaded from $fpath
+    _update_dns "$@"
+else
+    compdef _update_dns update-dns
+fi
+`, strings.Join(recordTypes(), " "), strings.Join(typeFlags, "|"),
+		strings.Join(formatFlags, "|"), strings.Join(profileFlags, "|"),
+		strings.Join(profileNames(), " "), strings.Join(flagNames(), " "),
+		strings.Join(commandNames, " "))
+}
+
+func fishCompletion(w io.Writer) {
+	fmt.Fprintf(w, "complete -c update-dns -n __fish_use_subcommand -f -a '%s'\n",
+		strings.Join(commandNames, " "))
+	fmt.Fprintf(w, "complete -c update-dns -n 'not __fish_use_subcommand' -f -a '%s'\n",
+		strings.Join(recordTypes(), " "))
+
+	commandFlags.VisitAll(func(f *pflag.Flag) {
+		line := "complete -c update-dns -l " + f.Name
+		if len(f.Shorthand) > 0 {
+			line += " -s " + f.Shorthand
+		}
+		switch f.Name {
+		case "type":
+			line += " -x -a '" + strings.Join(recordTypes(), " ") + "'"
+		case "output":
+			line += " -x -a 'text json'"
+		case "profile":
+			line += " -x -a '" + strings.Join(profileNames(), " ") + "'"
+		default:
+			if f.Value.Type() != "bool" {
+				line += " -r"
+			}
+		}
+		fmt.Fprintf(w, "%s -d '%s'\n", line, strings.Replace(f.Usage, "'", `\'`, -1))
+	})
+}
+
+func completions(args []string) int {
+	if len(args) != 1 {
+		log.Print("Usage: update-dns completions bash|zsh|fish")
+		return 1
+	}
+
+	switch args[0] {
+	case "bash":
+		bashCompletion(os.Stdout)
+	case "zsh":
+		zshCompletion(os.Stdout)
+	case "fish":
+		fishCompletion(os.Stdout)
+	default:
+		log.Printf("Unsupported shell: %s", args[0])
+		return 1
+	}
+	return 0
+}
@@ -0,0 +1,583 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"bufio"
+	"bytes"
+	"encoding/base64"
+	"encoding/json"
+	"fmt"
+	"log"
+	"net"
+	"os"
+	"os/exec"
+	"path/filepath"
+	"sort"
+	"strconv"
+	"strings"
+
+	"github.com/miekg/dns"
+	"github.com/spf13/viper"
+	"golang.org/x/term"
+)
+
+// The config files that have been read, and the user's own config file, if
+// there is one.
+var configFiles []string
+var userConfig string
+
+// userConfigFile returns the user's config file, or the default YAML file
+// if there isn't one yet.
+func userConfigFile() string {
+	if len(userConfig) > 0 {
+		return userConfig
+	}
+	return filepath.Join(os.Getenv("HOME"), ".update-dns", "update-dns.yaml")
+}
+
+// findConfigFile returns the update-dns config file in dir, in any of the
+// supported formats, or "" if there is none.
+func findConfigFile(dir string) string {
+	for _, ext := range viper.SupportedExts {
+		file := filepath.Join(dir, "update-dns."+ext)
+		if _, err := os.Stat(file); err == nil {
+			return file
+		}
+	}
+	return ""
+}
+
+// readConfigFile reads and parses file on its own. Files encrypted with
+// SOPS (which have a sops section with the encryption metadata), or marked
+// with "encrypted: true", are decrypted with "sops --decrypt". The returned
+// data is the decrypted contents.
+func readConfigFile(file string) (*viper.Viper, []byte, error) {
+	data, err := os.ReadFile(file)
+	if err != nil {
+		return nil, nil, err
+	}
+
+	v := viper.New()
+	v.SetConfigType(strings.TrimPrefix(filepath.Ext(file), "."))
+	if err := v.ReadConfig(bytes.NewReader(data)); err != nil {
+		return nil, nil, fmt.Errorf("%s: %s", file, err)
+	}
+	if !v.IsSet("sops") && !v.GetBool("encrypted") {
+		return v, data, nil
+	}
+
+	var stderr bytes.Buffer
+	cmd := exec.Command("sops", "--decrypt", file)
+	cmd.Stderr = &stderr
+	if data, err = cmd.Output(); err != nil {
+		return nil, nil, fmt.Errorf("unable to decrypt %s: %s %s", file, err,
+			strings.TrimSpace(stderr.String()))
+	}
+
+	v = viper.New()
+	v.SetConfigType(strings.TrimPrefix(filepath.Ext(file), "."))
+	if err := v.ReadConfig(bytes.NewReader(data)); err != nil {
+		return nil, nil, fmt.Errorf("%s (decrypted): %s", file, err)
+	}
+	return v, data, nil
+}
+
+// The config files being merged, as absolute paths, to detect include
+// cycles.
+var merging = make(map[string]bool)
+
+// mergeConfigFile merges the settings in file over the ones read so far,
+// followed by the files matching the patterns in its include setting.
+// Relative patterns are relative to the directory of file.
+func mergeConfigFile(file string) error {
+	// An include of a file being merged would never end
+	path, err := filepath.Abs(file)
+	if err != nil {
+		return err
+	}
+	if merging[path] {
+		return fmt.Errorf("%s: included from itself", file)
+	}
+	merging[path] = true
+	defer delete(merging, path)
+
+	v, data, err := readConfigFile(file)
+	if err != nil {
+		return err
+	}
+
+	viper.SetConfigType(strings.TrimPrefix(filepath.Ext(file), "."))
+	err = viper.MergeConfig(bytes.NewReader(data))
+	viper.SetConfigType("")
+	if err != nil {
+		return fmt.Errorf("%s: %s", file, err)
+	}
+	configFiles = append(configFiles, file)
+
+	for _, pattern := range v.GetStringSlice("include") {
+		if !filepath.IsAbs(pattern) {
+			pattern = filepath.Join(filepath.Dir(file), pattern)
+		}
+		files, err := filepath.Glob(pattern)
+		if err != nil {
+			return fmt.Errorf("invalid include pattern %s: %s", pattern, err)
+		}
+		for _, included := range files {
+			if err := mergeConfigFile(included); err != nil {
+				return err
+			}
+		}
+	}
+	return nil
+}
+
+// loadConfig reads the given config file, or else the system-wide config
+// file in /etc/update-dns with the user's config file merged over it.
+// Having no config file at all is fine, since the settings can come from
+// the environment.
+func loadConfig(file string) error {
+	if len(file) == 0 {
+		if system := findConfigFile("/etc/update-dns"); len(system) > 0 {
+			if err := mergeConfigFile(system); err != nil {
+				return err
+			}
+		}
+		file = findConfigFile(filepath.Join(os.Getenv("HOME"), ".update-dns"))
+		if len(file) == 0 {
+			return nil
+		}
+	}
+
+	userConfig = file
+	return mergeConfigFile(file)
+}
+
+// selectProfile merges the settings of a named profile from the profiles
+// section of the config file over the top-level settings. Flags still
+// take precedence.
+func selectProfile(name string) {
+	if !viper.IsSet("profiles." + name) {
+		log.Panicf("Unknown profile: %s", name)
+	}
+	if err := viper.MergeConfigMap(viper.GetStringMap("profiles." + name)); err != nil {
+		log.Panicf("Error reading profile %s: %s", name, err)
+	}
+}
+
+// profileNames returns the names of the profiles in the config file.
+func profileNames() []string {
+	var names []string
+	for name := range viper.GetStringMap("profiles") {
+		names = append(names, name)
+	}
+	sort.Strings(names)
+	return names
+}
+
+// isConfigKey returns true if line sets the top-level key in a YAML or TOML
+// file.
+func isConfigKey(line, key string, toml bool) bool {
+	if !strings.HasPrefix(line, key) {
+		return false
+	}
+	rest := strings.TrimSpace(line[len(key):])
+	if toml {
+		return strings.HasPrefix(rest, "=")
+	}
+	return strings.HasPrefix(rest, ":")
+}
+
+// setJSONConfigValues sets top-level keys in the contents of a JSON config
+// file.
+func setJSONConfigValues(data []byte, values [][2]string) ([]byte, error) {
+	config := make(map[string]interface{})
+	if len(data) > 0 {
+		if err := json.Unmarshal(data, &config); err != nil {
+			return nil, err
+		}
+	}
+	for _, kv := range values {
+		config[kv[0]] = kv[1]
+	}
+
+	data, err := json.MarshalIndent(config, "", "  ")
+	return append(data, '\n'), err
+}
+
+// writeConfigFile replaces file with data. It is written to a temporary
+// file in the same directory first, so the old config is only replaced
+// once the new one is complete.
+func writeConfigFile(file string, data []byte) error {
+	if err := os.MkdirAll(filepath.Dir(file), 0700); err != nil {
+		return err
+	}
+	tmp, err := os.CreateTemp(filepath.Dir(file), ".update-dns-config")
+	if err != nil {
+		return err
+	}
+	_, err = tmp.Write(data)
+	if cerr := tmp.Close(); err == nil {
+		err = cerr
+	}
+	// The file contains the secret, so only the user may read it
+	if err == nil {
+		err = os.Chmod(tmp.Name(), 0600)
+	}
+	if err == nil {
+		err = os.Rename(tmp.Name(), file)
+	}
+	if err != nil {
+		os.Remove(tmp.Name())
+	}
+	return err
+}
+
+// setConfigValues sets top-level keys in a YAML, TOML or JSON config file
+// (chosen by the file extension), replacing existing values and adding new
+// ones. In YAML and TOML files, the rest of the file is left as it is.
+func setConfigValues(file string, values [][2]string) error {
+	data, err := os.ReadFile(file)
+	if err != nil && !os.IsNotExist(err) {
+		return err
+	}
+	return writeConfigValues(file, data, values)
+}
+
+// writeConfigValues writes data, the old contents of file, to it with
+// values set (see setConfigValues).
+func writeConfigValues(file string, data []byte, values [][2]string) error {
+	if strings.EqualFold(filepath.Ext(file), ".json") {
+		data, err := setJSONConfigValues(data, values)
+		if err != nil {
+			return err
+		}
+		return writeConfigFile(file, data)
+	}
+	toml := strings.EqualFold(filepath.Ext(file), ".toml")
+
+	lines := strings.Split(strings.TrimRight(string(data), "\n"), "\n")
+	if len(data) == 0 {
+		lines = nil
+	}
+	for _, kv := range values {
+		line := kv[0] + ": " + strconv.Quote(kv[1])
+		if toml {
+			line = kv[0] + " = " + strconv.Quote(kv[1])
+		}
+
+		found := false
+		for i, l := range lines {
+			// In TOML, everything after the first table header is
+			// inside a table
+			if toml && strings.HasPrefix(strings.TrimSpace(l), "[") {
+				break
+			}
+			if isConfigKey(l, kv[0], toml) {
+				lines[i], found = line, true
+			}
+		}
+
+		if found {
+			continue
+		} else if toml {
+			lines = append([]string{line}, lines...)
+		} else {
+			lines = append(lines, line)
+		}
+	}
+
+	return writeConfigFile(file, []byte(strings.Join(lines, "\n")+"\n"))
+}
+
+func prompt(r *bufio.Reader, question, def string) string {
+	if len(def) > 0 {
+		fmt.Printf("%s [%s]: ", question, def)
+	} else {
+		fmt.Printf("%s: ", question)
+	}
+	line, _ := r.ReadString('\n')
+	line = strings.TrimSpace(line)
+	if len(line) == 0 {
+		return def
+	}
+	return line
+}
+
+func promptSecret(r *bufio.Reader, question string) string {
+	fd := int(os.Stdin.Fd())
+	if !term.IsTerminal(fd) {
+		return prompt(r, question, "")
+	}
+
+	fmt.Printf("%s: ", question)
+	secret, _ := term.ReadPassword(fd)
+	fmt.Println()
+	return strings.TrimSpace(string(secret))
+}
+
+// configInit asks for the server and key, checks that they work, and writes
+// them to the user's config file.
+func configInit() int {
+	file := userConfigFile()
+	r := bufio.NewReader(os.Stdin)
+
+	if _, err := os.Stat(file); err == nil {
+		answer := prompt(r, fmt.Sprintf("%s already exists. Overwrite? (y/N)", file), "n")
+		if !strings.HasPrefix(strings.ToLower(answer), "y") {
+			return 1
+		}
+	}
+
+	server := prompt(r, "Server to send updates to", "")
+	if len(server) == 0 {
+		log.Print("No server given")
+		return 1
+	}
+	if _, _, err := net.SplitHostPort(server); err != nil {
+		server = net.JoinHostPort(server, "53")
+	}
+
+	var key bindKey
+	keyFile := prompt(r, "BIND key file to read the key from (leave empty to enter it)", "")
+	if len(keyFile) > 0 {
+		keys, err := readBindKeys(keyFile)
+		if err != nil {
+			log.Printf("Unable to read key file: %s", err)
+			return 1
+		}
+		key = keys[0]
+	} else {
+		key.name = dns.Fqdn(prompt(r, "TSIG key name", ""))
+		key.algorithm = prompt(r, "TSIG algorithm", "hmac-sha256")
+		key.secret = promptSecret(r, "TSIG secret (base64)")
+	}
+
+	if _, ok := lookupTsigAlgorithm(key.algorithm); !ok {
+		log.Printf("Unknown algorithm: %s", key.algorithm)
+		return 1
+	} else if key.name == "." || len(key.secret) == 0 {
+		log.Print("Missing key name or secret")
+		return 1
+	}
+
+	overrideSetting("server", server)
+	overrideSetting("tsig-name", key.name)
+	overrideSetting("tsig-secret", key.secret)
+	overrideSetting("tsig-algorithm", key.algorithm)
+
+	zone := prompt(r, "Zone to test the key with", strings.SplitN(key.name, ".", 2)[1])
+	if len(zone) > 0 {
+		if _, err := lookup(zone, dns.TypeSOA); err != nil {
+			log.Printf("Signed test query failed: %s", err)
+			answer := prompt(r, "Write the config anyway? (y/N)", "n")
+			if !strings.HasPrefix(strings.ToLower(answer), "y") {
+				return 1
+			}
+		} else {
+			infof("Signed test query succeeded")
+		}
+	}
+
+	// The old config is replaced, not updated, but only once the new one
+	// has been written
+	err := writeConfigValues(file, nil, [][2]string{
+		{"server", server},
+		{"tsig-name", key.name},
+		{"tsig-algorithm", key.algorithm},
+		{"tsig-secret", key.secret},
+	})
+	if err != nil {
+		log.Printf("Unable to write config: %s", err)
+		return 1
+	}
+
+	infof("Wrote %s", file)
+	return 0
+}
+
+// configSetSecret stores the TSIG secret in the platform keyring and sets
+// the config to read it from there.
+func configSetSecret(args []string) int {
+	r := bufio.NewReader(os.Stdin)
+
+	keyName := tsigName(".")
+	if len(args) > 0 {
+		keyName = args[0]
+	}
+	if len(keyName) == 0 {
+		keyName = prompt(r, "TSIG key name", "")
+	}
+	keyName = dns.Fqdn(keyName)
+
+	secret := promptSecret(r, "TSIG secret (base64)")
+	if len(secret) == 0 {
+		log.Print("No secret given")
+		return 1
+	}
+
+	if err := keyringSet(keyName, secret); err != nil {
+		log.Printf("Unable to store the secret in the keyring: %s", err)
+		return 1
+	}
+
+	file := userConfigFile()
+	err := setConfigValues(file, [][2]string{
+		{"tsig-name", keyName},
+		{"tsig-secret-keyring", "true"},
+	})
+	if err != nil {
+		log.Printf("Unable to write config: %s", err)
+		return 1
+	}
+
+	if len(viper.GetString("tsig-secret")) > 0 {
+		infof("Stored the secret in the keyring; remove tsig-secret from %s to use it", file)
+	} else {
+		infof("Stored the secret in the keyring and updated %s", file)
+	}
+	return 0
+}
+
+// catchPanic runs f, turning a panic (from a config error) into an error.
+func catchPanic(f func() error) (err error) {
+	defer func() {
+		if r := recover(); r != nil {
+			err = fmt.Errorf("%v", r)
+		}
+	}()
+	return f()
+}
+
+// validateSettings checks the server and key settings used for names in
+// zone (or the top-level settings for "."), returning the problems found.
+func validateSettings(zone string) []string {
+	var problems []string
+
+	if server := setting(zone, "server"); len(server) == 0 {
+		problems = append(problems, "missing server")
+	} else if host, _, err := net.SplitHostPort(server); err != nil {
+		problems = append(problems, fmt.Sprintf("invalid server %s: %s", server, err))
+	} else if _, err := net.LookupHost(host); err != nil {
+		problems = append(problems, fmt.Sprintf("unable to resolve server %s: %s", host, err))
+	}
+
+	var keyName, alg, secret string
+	if err := catchPanic(func() error { keyName = tsigName(zone); return nil }); err != nil {
+		problems = append(problems, err.Error())
+	} else if len(keyName) == 0 {
+		problems = append(problems, "missing tsig-name")
+	}
+	if err := catchPanic(func() error { alg = tsigAlgorithm(zone); return nil }); err != nil {
+		problems = append(problems, err.Error())
+	}
+	if err := catchPanic(func() error { secret = tsigSecret(zone); return nil }); err != nil {
+		problems = append(problems, err.Error())
+	} else if len(secret) == 0 {
+		problems = append(problems, "missing tsig-secret")
+	} else if key, err := base64.StdEncoding.DecodeString(secret); err != nil {
+		problems = append(problems, fmt.Sprintf("tsig-secret is not valid base64: %s", err))
+	} else if len(alg) > 0 && len(key) < tsigKeySizes[alg] {
+		problems = append(problems, fmt.Sprintf("tsig-secret is %d bytes, shorter than the %d bytes needed for %s",
+			len(key), tsigKeySizes[alg], strings.TrimSuffix(alg, ".")))
+	}
+
+	return problems
+}
+
+// configValidate checks the top-level settings and those of each zone in
+// the zones section, and prints the problems found. If a zone is given, a
+// signed query for its SOA record checks that the server accepts the key.
+func configValidate(args []string) int {
+	if len(args) > 1 {
+		log.Print("Usage: update-dns config validate [zone]")
+		return 1
+	}
+
+	if len(configFiles) > 0 {
+		fmt.Printf("Config files: %s\n", strings.Join(configFiles, ", "))
+	} else {
+		fmt.Println("No config file found")
+	}
+
+	if err := catchPanic(func() error { readZoneRoutes(); readReverseZones(); return nil }); err != nil {
+		fmt.Printf("config: %s\n", err)
+		return 1
+	}
+
+	// With per-zone settings, the top-level ones are optional
+	zones := []string{}
+	if len(zoneRoutes) == 0 || len(viper.GetString("server")) > 0 {
+		zones = append(zones, ".")
+	}
+	var routed []string
+	for zone := range zoneRoutes {
+		routed = append(routed, zone)
+	}
+	sort.Strings(routed)
+	zones = append(zones, routed...)
+
+	problems := 0
+	for _, zone := range zones {
+		scope := "zone " + zone
+		if zone == "." {
+			scope = "top level"
+		}
+		for _, problem := range validateSettings(zone) {
+			fmt.Printf("%s: %s\n", scope, problem)
+			problems++
+		}
+	}
+
+	if len(args) == 1 {
+		zone := dns.Fqdn(args[0])
+		err := catchPanic(func() error {
+			_, err := lookup(zone, dns.TypeSOA)
+			return err
+		})
+		if err != nil {
+			fmt.Printf("zone %s: signed query failed: %s\n", zone, err)
+			problems++
+		} else {
+			fmt.Printf("zone %s: signed query succeeded\n", zone)
+		}
+	}
+
+	if problems > 0 {
+		return 1
+	}
+	fmt.Println("No problems found")
+	return 0
+}
+
+func configCommand(args []string) int {
+	if len(args) < 1 {
+		log.Print("Usage: update-dns config init|set-secret [key name]|validate [zone]")
+		return 1
+	}
+
+	switch args[0] {
+	case "init":
+		return configInit()
+	case "set-secret":
+		return configSetSecret(args[1:])
+	case "validate":
+		return configValidate(args[1:])
+	}
+
+	log.Printf("Unknown config command: %s", args[0])
+	return 1
+}
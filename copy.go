@@ -0,0 +1,70 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"log"
+)
+
+func copyRecords(args []string) int {
+	if len(args) < 2 || len(args) > 3 {
+		log.Print("Usage: update-dns copy <src> <dst> [type]")
+		return 1
+	}
+	src, dst := expandName(args[0]), expandName(args[1])
+
+	var rrtype uint16
+	if len(args) > 2 {
+		t, err := parseType(args[2])
+		if err != nil {
+			log.Print(err)
+			return 1
+		}
+		rrtype = t
+	}
+
+	rrs, err := recordsAt(src)
+	if err != nil {
+		log.Printf("Unable to look up %s: %s", src, err)
+		return 1
+	}
+
+	var ops []operation
+	for _, rr := range rrs {
+		if rrtype != 0 && rr.Header().Rrtype != rrtype {
+			continue
+		}
+		ops = append(ops, operation{"replace", withName(rr, dst)})
+	}
+	if len(ops) == 0 {
+		log.Printf("No matching records found at %s", src)
+		return 1
+	}
+
+	zone := getZone(dst)
+	if err := checkGuards(zone, ops, true); err != nil {
+		log.Printf("Not sending update: %s", err)
+		return 1
+	}
+	err = update(zone, ops)
+	if err != nil {
+		log.Printf("Unable to send update: %s", err)
+		return exitCode(err)
+	}
+
+	infof("Copied %d records from %s to %s", len(ops), src, dst)
+	return 0
+}
@@ -0,0 +1,312 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"bytes"
+	"errors"
+	"fmt"
+	"io"
+	"log"
+	"net"
+	"os"
+	"sort"
+	"strings"
+	"time"
+
+	"github.com/miekg/dns"
+	"github.com/spf13/viper"
+)
+
+// A daemonRequest is a batch submitted over the control socket, not yet
+// parsed. The result is sent back on reply.
+type daemonRequest struct {
+	input []byte
+	reply chan error
+}
+
+// readOps parses a batch with readBatch. As in applyOps, a panic (such as
+// over an invalid domain setting) is returned instead of ending the
+// program.
+func readOps(in io.Reader) (ops []operation, err error) {
+	defer recoverError(&err)
+	return readBatch(in)
+}
+
+// applyOps sends ops, grouped into one update per zone. Fatal errors (such
+// as failing zone discovery) are returned instead of ending the program.
+// If several zones fail, the error of the first is wrapped, so Kind
+// returns its kind.
+func applyOps(ops []operation) (err error) {
+	span := startSpan("apply", "update_dns.operations", fmt.Sprint(len(ops)))
+	defer func() { span.finish(err) }()
+	defer recoverError(&err)
+
+	// Nothing is sent if the guards refuse any of the updates
+	updates := groupByZone(ops)
+	for _, u := range updates {
+		if err := checkGuards(u.zone, u.ops, false); err != nil {
+			return kindError(ErrValidation, fmt.Errorf("zone %s: %s", u.zone, err))
+		}
+	}
+
+	var first error
+	var others []string
+	for _, u := range updates {
+		if err := update(u.zone, u.ops); err == nil {
+			continue
+		} else if first == nil {
+			first = fmt.Errorf("zone %s: %w", u.zone, err)
+		} else {
+			others = append(others, fmt.Sprintf("zone %s: %s", u.zone, err))
+		}
+	}
+	if len(others) > 0 {
+		return fmt.Errorf("%w; %s", first, strings.Join(others, "; "))
+	}
+	return first
+}
+
+// A fileWatcher re-applies a batch file whenever it is modified.
+type fileWatcher struct {
+	path    string
+	modTime time.Time
+}
+
+func (w *fileWatcher) poll() {
+	fi, err := os.Stat(w.path)
+	if err != nil {
+		log.Printf("Unable to check %s: %s", w.path, err)
+		return
+	}
+	if fi.ModTime().Equal(w.modTime) {
+		return
+	}
+	w.modTime = fi.ModTime()
+
+	f, err := os.Open(w.path)
+	if err != nil {
+		log.Printf("Unable to open %s: %s", w.path, err)
+		return
+	}
+	defer f.Close()
+
+	ops, err := readOps(f)
+	if err != nil {
+		log.Printf("Unable to parse %s: %s", w.path, err)
+		return
+	}
+	if err := applyOps(ops); err != nil {
+		log.Printf("Unable to apply %s: %s", w.path, err)
+		return
+	}
+	infof("Applied %s (%d operations)", w.path, len(ops))
+}
+
+// An ifaceWatcher keeps the A and AAAA records of a name in sync with the
+// global addresses of a network interface.
+type ifaceWatcher struct {
+	iface string
+	name  string
+	addrs string
+}
+
+func interfaceAddrs(name string) ([]net.IP, error) {
+	iface, err := net.InterfaceByName(name)
+	if err != nil {
+		return nil, err
+	}
+	addrs, err := iface.Addrs()
+	if err != nil {
+		return nil, err
+	}
+
+	var ips []net.IP
+	for _, a := range addrs {
+		if ipnet, ok := a.(*net.IPNet); ok && ipnet.IP.IsGlobalUnicast() {
+			ips = append(ips, ipnet.IP)
+		}
+	}
+	return ips, nil
+}
+
+// addressOps returns the operations setting the A and AAAA RRsets of name to
+// ips. An address family without addresses has its RRset deleted.
+func addressOps(name string, ips []net.IP, ttl uint32) []operation {
+	var v4, v6 []operation
+	for _, ip := range ips {
+		hdr := dns.RR_Header{Name: name, Class: dns.ClassINET, Ttl: ttl}
+		if ip.To4() != nil {
+			hdr.Rrtype = dns.TypeA
+			v4 = append(v4, operation{"replace", &dns.A{Hdr: hdr, A: ip.To4()}})
+		} else {
+			hdr.Rrtype = dns.TypeAAAA
+			v6 = append(v6, operation{"replace", &dns.AAAA{Hdr: hdr, AAAA: ip}})
+		}
+	}
+
+	if len(v4) == 0 {
+		v4 = []operation{{"delete", &dns.A{Hdr: dns.RR_Header{Name: name, Rrtype: dns.TypeA}}}}
+	}
+	if len(v6) == 0 {
+		v6 = []operation{{"delete", &dns.AAAA{Hdr: dns.RR_Header{Name: name, Rrtype: dns.TypeAAAA}}}}
+	}
+	return append(v4, v6...)
+}
+
+func (w *ifaceWatcher) poll() {
+	ips, err := interfaceAddrs(w.iface)
+	if err != nil {
+		log.Printf("Unable to get addresses of %s: %s", w.iface, err)
+		return
+	}
+
+	var strs []string
+	for _, ip := range ips {
+		strs = append(strs, ip.String())
+	}
+	sort.Strings(strs)
+	addrs := strings.Join(strs, " ")
+	if addrs == w.addrs {
+		return
+	}
+
+	ops := addressOps(w.name, ips, uint32(viper.GetInt("daemon.ttl")))
+	if err := applyOps(ops); err != nil {
+		log.Printf("Unable to update %s: %s", w.name, err)
+		return
+	}
+	w.addrs = addrs
+	infof("Updated %s to the addresses of %s: %s", w.name, w.iface, addrs)
+}
+
+// listenSocket accepts batch input on a unix socket. Each connection sends
+// a batch (terminated by closing the sending side), and gets back "OK" or
+// an error message once it has been applied. The connections only read the
+// input; it is parsed and applied by the daemon loop.
+func listenSocket(path string, requests chan<- daemonRequest) error {
+	os.Remove(path)
+	l, err := net.Listen("unix", path)
+	if err != nil {
+		return err
+	}
+	os.Chmod(path, 0660)
+
+	go func() {
+		// Wait a bit longer after each failure in a row (e.g. when out of
+		// file descriptors), as net/http does
+		var delay time.Duration
+		for {
+			conn, err := l.Accept()
+			if errors.Is(err, net.ErrClosed) {
+				return
+			} else if err != nil {
+				if delay *= 2; delay == 0 {
+					delay = 5 * time.Millisecond
+				} else if delay > time.Second {
+					delay = time.Second
+				}
+				log.Printf("Unable to accept connection: %s; retrying in %s", err, delay)
+				time.Sleep(delay)
+				continue
+			}
+			delay = 0
+			go handleConn(conn, requests)
+		}
+	}()
+	return nil
+}
+
+func handleConn(conn net.Conn, requests chan<- daemonRequest) {
+	defer conn.Close()
+
+	input, err := io.ReadAll(conn)
+	if err == nil {
+		req := daemonRequest{input, make(chan error)}
+		requests <- req
+		err = <-req.reply
+	}
+
+	if err != nil {
+		fmt.Fprintf(conn, "ERROR: %s\n", err)
+		return
+	}
+	fmt.Fprintln(conn, "OK")
+}
+
+func daemon(args []string) int {
+	if len(args) != 0 {
+		log.Print("Usage: update-dns daemon")
+		return 1
+	}
+
+	var files []*fileWatcher
+	for _, path := range viper.GetStringSlice("daemon.watch") {
+		files = append(files, &fileWatcher{path: path})
+	}
+
+	var ifaces []*ifaceWatcher
+	for iface, name := range viper.GetStringMapString("daemon.interfaces") {
+		ifaces = append(ifaces, &ifaceWatcher{iface: iface, name: dns.Fqdn(name)})
+	}
+
+	requests := make(chan daemonRequest)
+	socket := viper.GetString("daemon.socket")
+	if len(socket) > 0 {
+		if err := listenSocket(socket, requests); err != nil {
+			log.Printf("Unable to listen on %s: %s", socket, err)
+			return 1
+		}
+		// The loop below only ends with the program, through exit
+		atExit = append(atExit, func() { os.Remove(socket) })
+	}
+
+	if len(files) == 0 && len(ifaces) == 0 && len(socket) == 0 {
+		log.Print("Nothing to do; configure daemon.watch, daemon.interfaces or daemon.socket")
+		return 1
+	}
+
+	sharedConn = new(connCache)
+	defer sharedConn.close()
+	tracing = len(tracingEndpoint()) > 0
+	signalExitcode = 0
+
+	// The batches are parsed and the updates made in this goroutine only,
+	// so the settings and caches aren't used concurrently
+	ticker := time.NewTicker(viper.GetDuration("daemon.interval"))
+	defer ticker.Stop()
+	for {
+		// Only the results of the current cycle are kept (for the JSON
+		// output, report and metrics at exit), so they don't pile up
+		updateResults = nil
+		for _, w := range files {
+			w.poll()
+		}
+		for _, w := range ifaces {
+			w.poll()
+		}
+
+		select {
+		case req := <-requests:
+			ops, err := readOps(bytes.NewReader(req.input))
+			if err == nil {
+				err = applyOps(ops)
+			}
+			req.reply <- err
+		case <-ticker.C:
+		}
+	}
+}
@@ -0,0 +1,161 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"context"
+	"fmt"
+	"io"
+	"log"
+	"net"
+	"net/http"
+	"strings"
+	"time"
+
+	"github.com/miekg/dns"
+	"github.com/spf13/viper"
+)
+
+// publicIP asks the web service at url for our public address, connecting
+// over network ("tcp4" or "tcp6") so we get the address of that family.
+func publicIP(url, network string) (net.IP, error) {
+	dialer := &net.Dialer{Timeout: 10 * time.Second}
+	client := &http.Client{
+		Timeout: 15 * time.Second,
+		Transport: &http.Transport{
+			DialContext: func(ctx context.Context, _, addr string) (net.Conn, error) {
+				return dialer.DialContext(ctx, network, addr)
+			},
+		},
+	}
+
+	resp, err := client.Get(url)
+	if err != nil {
+		return nil, err
+	}
+	defer resp.Body.Close()
+
+	if resp.StatusCode != http.StatusOK {
+		return nil, fmt.Errorf("%s returned %s", url, resp.Status)
+	}
+
+	body, err := io.ReadAll(io.LimitReader(resp.Body, 256))
+	if err != nil {
+		return nil, err
+	}
+
+	ip := net.ParseIP(strings.TrimSpace(string(body)))
+	if ip == nil || (ip.To4() != nil) != (network == "tcp4") {
+		return nil, fmt.Errorf("%s returned an invalid address", url)
+	}
+	return ip, nil
+}
+
+// currentAddress returns the address in the RRset of the given type at
+// name, if it consists of exactly one record.
+func currentAddress(name string, rrtype uint16) (net.IP, error) {
+	rrs, err := lookup(name, rrtype)
+	if err != nil {
+		return nil, err
+	}
+
+	var ip net.IP
+	for _, rr := range rrs {
+		if rr.Header().Rrtype != rrtype {
+			continue
+		}
+		if ip != nil {
+			return nil, nil
+		}
+		ip = recordAddress(rr)
+	}
+	return ip, nil
+}
+
+func ddns(args []string) int {
+	if len(args) != 1 {
+		log.Print("Usage: update-dns ddns <hostname>")
+		return 1
+	}
+	name := dns.Fqdn(args[0])
+	ttl := uint32(viper.GetInt("ddns.ttl"))
+
+	families := []struct {
+		url     string
+		network string
+		rrtype  uint16
+	}{
+		{viper.GetString("ddns.ipv4-url"), "tcp4", dns.TypeA},
+		{viper.GetString("ddns.ipv6-url"), "tcp6", dns.TypeAAAA},
+	}
+
+	var ops []operation
+	for _, f := range families {
+		if len(f.url) == 0 {
+			continue
+		}
+
+		ip, err := publicIP(f.url, f.network)
+		if err != nil {
+			// Not having an address of one family is normal, so keep
+			// the records as they are
+			log.Printf("No public %s address: %s", dns.TypeToString[f.rrtype], err)
+			continue
+		}
+
+		current, err := currentAddress(name, f.rrtype)
+		if err != nil {
+			log.Printf("Unable to look up %s: %s", name, err)
+			return 1
+		}
+		if current.Equal(ip) {
+			infof("%s record for %s is up to date (%s)", dns.TypeToString[f.rrtype], name, ip)
+			continue
+		}
+
+		hdr := dns.RR_Header{Name: name, Rrtype: f.rrtype, Class: dns.ClassINET, Ttl: ttl}
+		if f.rrtype == dns.TypeA {
+			ops = append(ops, operation{"replace", &dns.A{Hdr: hdr, A: ip.To4()}})
+		} else {
+			ops = append(ops, operation{"replace", &dns.AAAA{Hdr: hdr, AAAA: ip}})
+		}
+		infof("Updating %s record for %s to %s", dns.TypeToString[f.rrtype], name, ip)
+	}
+
+	if len(ops) == 0 {
+		return 0
+	}
+
+	zone := getZone(name)
+	if err := checkGuards(zone, ops, true); err != nil {
+		log.Printf("Not sending update: %s", err)
+		return 1
+	}
+
+	var err error
+	if reverseEnabled(name) {
+		err = updateWithReverse(zone, ops)
+	} else {
+		err = update(zone, ops)
+	}
+	if err != nil {
+		log.Printf("Unable to send update: %s", err)
+		return exitCode(err)
+	}
+
+	infof("Update successful")
+	return 0
+}
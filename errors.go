@@ -0,0 +1,118 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"errors"
+	"net"
+)
+
+// ErrorKind tells what kind of failure caused an error returned by the
+// library, e.g. to decide whether to retry.
+type ErrorKind int
+
+const (
+	ErrOther      ErrorKind = iota
+	ErrConfig               // invalid or missing settings
+	ErrResolution           // the zone of a name couldn't be found
+	ErrTransport            // no answer from the server
+	ErrTSIG                 // the server rejected the TSIG signature
+	ErrRcode                // the server answered with an error (see Rcode)
+	ErrValidation           // an invalid record, or a refused risky change
+	ErrPartial              // only some of the updates of a change were made
+	ErrCancelled            // the updates were cancelled
+)
+
+var errorKinds = map[ErrorKind]string{
+	ErrOther:      "other",
+	ErrConfig:     "config",
+	ErrResolution: "resolution",
+	ErrTransport:  "transport",
+	ErrTSIG:       "tsig",
+	ErrRcode:      "rcode",
+	ErrValidation: "validation",
+	ErrPartial:    "partial",
+	ErrCancelled:  "cancelled",
+}
+
+func (k ErrorKind) String() string {
+	return errorKinds[k]
+}
+
+// An Error is an error of a known kind. Errors of the other kinds (TSIG,
+// server and transport errors) have types of their own, which Kind knows.
+type Error struct {
+	Kind ErrorKind
+	Err  error
+}
+
+func (e *Error) Error() string { return e.Err.Error() }
+func (e *Error) Unwrap() error { return e.Err }
+
+func kindError(kind ErrorKind, err error) error {
+	if err == nil {
+		return nil
+	}
+	return &Error{kind, err}
+}
+
+// Kind returns the kind of err.
+func Kind(err error) ErrorKind {
+	var e *Error
+	var perr *partialError
+	var rerr *rcodeError
+	var nerr net.Error
+	var terr *tsigError
+
+	switch {
+	case err == nil:
+		return ErrOther
+	case errors.As(err, &terr):
+		return ErrTSIG
+	case errors.As(err, &perr):
+		return ErrPartial
+	case errors.As(err, &rerr):
+		return ErrRcode
+	case errors.As(err, &nerr):
+		return ErrTransport
+	case errors.As(err, &e):
+		return e.Kind
+	}
+	return ErrOther
+}
+
+// ExitCode returns the exit status the command line tool exits with for
+// err (see the README), or 0 if err is nil.
+func ExitCode(err error) int {
+	if err == nil {
+		return 0
+	}
+	return exitCode(err)
+}
+
+// Rcode returns the response code of an ErrRcode error (or of a TSIG
+// error), and whether err is one.
+func Rcode(err error) (int, bool) {
+	var rerr *rcodeError
+	var terr *tsigError
+	switch {
+	case errors.As(err, &terr):
+		return int(terr.code), true
+	case errors.As(err, &rerr):
+		return rerr.rcode, true
+	}
+	return 0, false
+}
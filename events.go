@@ -0,0 +1,106 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"time"
+
+	"github.com/miekg/dns"
+)
+
+// An EventType says what happened in an Event.
+type EventType string
+
+const (
+	EventZoneFound       EventType = "zone-found"          // the zone of Name is Zone
+	EventUpdateStarted   EventType = "update-started"      // an update of Zone is being sent
+	EventUpdateSent      EventType = "update-sent"         // the update of Zone succeeded
+	EventUpdateUnchanged EventType = "update-unchanged"    // the update changed nothing, so it wasn't sent
+	EventDryRun          EventType = "dry-run"             // the update wasn't sent, because of dry-run
+	EventUpdateFailed    EventType = "update-failed"       // the update of Zone failed with Err
+	EventPrereqFailed    EventType = "prerequisite-failed" // a prerequisite of the update didn't hold
+	EventRecordCreated   EventType = "record-created"      // the update added Record
+	EventRecordDeleted   EventType = "record-deleted"      // the update removed Record
+	EventPTRUpdated      EventType = "ptr-updated"         // the PTR record Record was updated
+	EventPTRFailed       EventType = "ptr-failed"          // the update of the PTR record Record failed
+	EventPTRSkipped      EventType = "ptr-skipped"         // no PTR record was updated for Name
+)
+
+// An Event reports the progress of the updates made through the library.
+type Event struct {
+	Time   time.Time
+	Type   EventType
+	Zone   string
+	Name   string // the name concerned, if any
+	Record string // the record concerned, in zone file format, if any
+	Err    error
+}
+
+// Set with Updater.SetEvents
+var eventChan chan<- Event
+
+// SetEvents makes the updater send events to ch as it makes updates, or
+// stops it if ch is nil. Events are dropped rather than waiting when ch is
+// full, so they don't hold up the updates; give it a buffer.
+func (u *Updater) SetEvents(ch chan<- Event) {
+	apiLock.Lock()
+	defer apiLock.Unlock()
+	eventChan = ch
+}
+
+func emit(e Event) {
+	if eventChan == nil {
+		return
+	}
+	e.Time = time.Now()
+	select {
+	case eventChan <- e:
+	default:
+	}
+}
+
+// emitUpdate sends the events for the result of an update of zone with
+// ops, which sendUpdate gave the status status.
+func emitUpdate(zone string, ops []operation, state *journalEntry, status string, err error) {
+	if eventChan == nil {
+		return
+	}
+
+	switch status {
+	case "sent":
+		emit(Event{Type: EventUpdateSent, Zone: zone})
+		removed, added := updateChanges(state, ops)
+		for _, rr := range removed {
+			emit(Event{Type: EventRecordDeleted, Zone: zone, Name: rr.Header().Name, Record: rr.String()})
+		}
+		for _, rr := range added {
+			emit(Event{Type: EventRecordCreated, Zone: zone, Name: rr.Header().Name, Record: rr.String()})
+		}
+	case "unchanged":
+		emit(Event{Type: EventUpdateUnchanged, Zone: zone})
+	case "dry-run":
+		emit(Event{Type: EventDryRun, Zone: zone})
+	default:
+		event := EventUpdateFailed
+		if rcode, ok := Rcode(err); ok {
+			switch rcode {
+			case dns.RcodeYXDomain, dns.RcodeYXRrset, dns.RcodeNXRrset, dns.RcodeNameError:
+				event = EventPrereqFailed
+			}
+		}
+		emit(Event{Type: event, Zone: zone, Err: err})
+	}
+}
@@ -0,0 +1,84 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"fmt"
+
+	"github.com/miekg/dns"
+)
+
+// Exit codes for the different kinds of failure, so scripts can tell
+// failures worth retrying from permanent ones. Config errors exit with
+// exitConfig from the panic handler in main.
+const (
+	exitFailure   = 1
+	exitConfig    = 2
+	exitNetwork   = 3 // no answer from the server
+	exitRefused   = 4 // NOTAUTH or REFUSED, e.g. a wrong key
+	exitPrereq    = 5 // a prerequisite of the update didn't hold
+	exitPartial   = 6 // some of the updates were made, but not all
+	exitUnchanged = 7 // with --detailed-exitcode: nothing needed changing
+	exitTimeout   = 8 // the --timeout expired
+	exitCancelled = 9 // interrupted by SIGINT or SIGTERM
+)
+
+// An rcodeError is returned when the server answers an update with an
+// error.
+type rcodeError struct {
+	rcode int
+}
+
+func (e *rcodeError) Error() string {
+	return fmt.Sprintf("Server refused registration. Code: %s", dns.RcodeToString[e.rcode])
+}
+
+// A partialError is returned when only some of the updates making up a
+// change were made.
+type partialError struct {
+	err error
+}
+
+func (e *partialError) Error() string { return e.err.Error() }
+func (e *partialError) Unwrap() error { return e.err }
+
+// The number of updates sent and skipped because they changed nothing,
+// for the --detailed-exitcode check.
+var updatesSent, updatesSkipped int
+
+func exitCode(err error) int {
+	switch Kind(err) {
+	case ErrConfig, ErrResolution:
+		return exitConfig
+	case ErrTSIG:
+		return exitRefused
+	case ErrPartial:
+		return exitPartial
+	case ErrRcode:
+		rcode, _ := Rcode(err)
+		switch rcode {
+		case dns.RcodeNotAuth, dns.RcodeRefused:
+			return exitRefused
+		case dns.RcodeYXDomain, dns.RcodeYXRrset, dns.RcodeNXRrset, dns.RcodeNameError:
+			return exitPrereq
+		}
+	case ErrTransport:
+		return exitNetwork
+	case ErrCancelled:
+		return exitCancelled
+	}
+	return exitFailure
+}
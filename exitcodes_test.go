@@ -0,0 +1,44 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"errors"
+	"net"
+	"testing"
+)
+
+func TestExitCodeDiscovery(t *testing.T) {
+	testConfig(t, map[string]interface{}{"zone-discovery": "static,soa"})
+	soa := zoneStrategies["soa"]
+	defer func() { zoneStrategies["soa"] = soa }()
+	zoneStrategies["soa"] = ZoneStrategyFunc(func(name string) (string, error) {
+		return "", &net.OpError{Op: "read", Net: "udp", Err: errors.New("connection refused")}
+	})
+
+	_, _, err := findZone("host.example.org.")
+	if err == nil {
+		t.Fatal("findZone succeeded")
+	}
+	if code := exitCode(kindError(ErrResolution, err)); code != exitNetwork {
+		t.Errorf("exit code of %q = %d, want %d", err, code, exitNetwork)
+	}
+
+	err = kindError(ErrResolution, errors.New("no SOA record found"))
+	if code := exitCode(err); code != exitConfig {
+		t.Errorf("exit code of %q = %d, want %d", err, code, exitConfig)
+	}
+}
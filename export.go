@@ -0,0 +1,103 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"bytes"
+	"fmt"
+	"log"
+	"os"
+	"sort"
+	"strings"
+
+	"github.com/miekg/dns"
+	"github.com/spf13/viper"
+)
+
+// canonicalLess orders names as in RFC 4034 section 6.1: label by label,
+// starting from the root.
+func canonicalLess(a, b string) bool {
+	la := dns.SplitDomainName(strings.ToLower(a))
+	lb := dns.SplitDomainName(strings.ToLower(b))
+	for i, j := len(la)-1, len(lb)-1; i >= 0 && j >= 0; i, j = i-1, j-1 {
+		if la[i] != lb[j] {
+			return la[i] < lb[j]
+		}
+	}
+	return len(la) < len(lb)
+}
+
+// sortRecords sorts records into a stable order suitable for zone files:
+// the SOA first, then by name, type and data.
+func sortRecords(rrs []dns.RR) {
+	sort.SliceStable(rrs, func(i, j int) bool {
+		hi, hj := rrs[i].Header(), rrs[j].Header()
+		if (hi.Rrtype == dns.TypeSOA) != (hj.Rrtype == dns.TypeSOA) {
+			return hi.Rrtype == dns.TypeSOA
+		}
+		if !strings.EqualFold(hi.Name, hj.Name) {
+			return canonicalLess(hi.Name, hj.Name)
+		}
+		if hi.Rrtype != hj.Rrtype {
+			return hi.Rrtype < hj.Rrtype
+		}
+		return rrs[i].String() < rrs[j].String()
+	})
+}
+
+func export(args []string) int {
+	if len(args) != 1 {
+		log.Print("Usage: update-dns export <zone> [--output-file <file>]")
+		return 1
+	}
+	zone := dns.Fqdn(args[0])
+
+	rrs, err := transfer(zone)
+	if err != nil {
+		log.Printf("Zone transfer failed: %s", err)
+		return 1
+	}
+	sortRecords(rrs)
+
+	var buf bytes.Buffer
+	fmt.Fprintf(&buf, "; Zone %s exported from %s\n", zone, setting(zone, "server"))
+	fmt.Fprintf(&buf, "$ORIGIN %s\n", zone)
+	for _, rr := range rrs {
+		fmt.Fprintln(&buf, rr.String())
+	}
+
+	file := viper.GetString("output-file")
+	if len(file) == 0 {
+		os.Stdout.Write(buf.Bytes())
+		return 0
+	}
+
+	// Write to a temporary file first, so an existing export is never
+	// left half-written
+	tmp := file + ".tmp"
+	if err := os.WriteFile(tmp, buf.Bytes(), 0644); err != nil {
+		log.Printf("Unable to write zone file: %s", err)
+		return 1
+	}
+	if err := os.Rename(tmp, file); err != nil {
+		os.Remove(tmp)
+		log.Printf("Unable to write zone file: %s", err)
+		return 1
+	}
+
+	infof("Exported %d records to %s", len(rrs), file)
+	return 0
+}
@@ -0,0 +1,13 @@
+module github.com/tohojo/update-dns
+
+go 1.19
+
+require (
+	github.com/miekg/dns v1.1.58
+	github.com/spf13/cast v1.6.0
+	github.com/spf13/pflag v1.0.5
+	github.com/spf13/viper v1.18.2
+	github.com/zalando/go-keyring v0.2.3
+	golang.org/x/net v0.20.0
+	golang.org/x/term v0.16.0
+)
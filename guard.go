@@ -0,0 +1,91 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"bufio"
+	"fmt"
+	"os"
+	"strings"
+
+	"github.com/miekg/dns"
+	"github.com/spf13/viper"
+	"golang.org/x/term"
+)
+
+// riskyChanges describes the changes in ops that can break a whole zone
+// and are enabled in the guard settings: changes to wildcard names or the
+// zone apex, and deletions of NS or SOA records.
+func riskyChanges(zone string, ops []operation) []string {
+	var risky []string
+	seen := make(map[string]bool)
+	add := func(desc string) {
+		if !seen[desc] {
+			risky = append(risky, desc)
+			seen[desc] = true
+		}
+	}
+
+	for _, op := range ops {
+		switch op.action {
+		case "replace", "append", "delete", "remove":
+		default:
+			continue
+		}
+
+		name := strings.ToLower(op.rr.Header().Name)
+		rrtype := op.rr.Header().Rrtype
+		if viper.GetBool("guard.wildcard") && strings.HasPrefix(name, "*.") {
+			add(fmt.Sprintf("changes wildcard name %s", name))
+		}
+		if viper.GetBool("guard.apex") && name == strings.ToLower(zone) {
+			add(fmt.Sprintf("changes the apex of zone %s", zone))
+		}
+		if viper.GetBool("guard.ns") && (op.action == "delete" || op.action == "remove") &&
+			(rrtype == dns.TypeNS || rrtype == dns.TypeSOA) {
+			add(fmt.Sprintf("deletes %s records at %s", dns.TypeToString[rrtype], name))
+		}
+	}
+	return risky
+}
+
+// The risky changes confirmed by the user, by zone, so they are asked only
+// once when both a command and sendUpdate check the same update.
+var confirmedChanges = make(map[string]bool)
+
+// checkGuards refuses risky changes unless --force is given. If confirm is
+// set and stdin is a terminal, the user is asked instead.
+func checkGuards(zone string, ops []operation, confirm bool) error {
+	risky := riskyChanges(zone, ops)
+	if len(risky) == 0 || viper.GetBool("force") || rollingBack {
+		return nil
+	}
+
+	desc := "the update " + strings.Join(risky, " and ")
+	if confirmedChanges[zone+" "+desc] {
+		return nil
+	}
+	if confirm && term.IsTerminal(int(os.Stdin.Fd())) {
+		answer := prompt(bufio.NewReader(os.Stdin), "Really send an update that "+
+			strings.TrimPrefix(desc, "the update ")+"? (y/N)", "n")
+		if strings.HasPrefix(strings.ToLower(answer), "y") {
+			confirmedChanges[zone+" "+desc] = true
+			return nil
+		}
+		return fmt.Errorf("not confirmed")
+	}
+	return fmt.Errorf("%s (use --force to send it anyway)", desc)
+}
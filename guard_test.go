@@ -0,0 +1,72 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"reflect"
+	"strings"
+	"testing"
+
+	"github.com/spf13/viper"
+)
+
+func TestRiskyChanges(t *testing.T) {
+	tests := []struct {
+		guards map[string]interface{}
+		ops    []string
+		want   []string
+	}{
+		{nil, []string{"append www.example.org. 300 IN A 192.0.2.1"}, nil},
+		{nil, []string{"replace *.example.org. 300 IN A 192.0.2.1", "replace *.example.org. 300 IN A 192.0.2.2"},
+			[]string{"changes wildcard name *.example.org."}},
+		{nil, []string{"append example.org. 300 IN TXT \"v=spf1 -all\""},
+			[]string{"changes the apex of zone example.org."}},
+		{nil, []string{"remove sub.example.org. 3600 IN NS ns.sub.example.org."},
+			[]string{"deletes NS records at sub.example.org."}},
+		{nil, []string{"remove example.org. 3600 IN NS ns.example.org."},
+			[]string{"changes the apex of zone example.org.", "deletes NS records at example.org."}},
+		// Prerequisites don't change anything; adding NS records is fine
+		{nil, []string{"yxrr example.org. 3600 IN NS ns.example.org.", "append sub.example.org. 3600 IN NS ns.example.net."}, nil},
+		{map[string]interface{}{"guard.apex": false, "guard.wildcard": false},
+			[]string{"append example.org. 300 IN TXT \"hello\"", "delete *.example.org. 300 IN A 192.0.2.1"}, nil},
+	}
+	for _, test := range tests {
+		testConfig(t, test.guards)
+		var ops []operation
+		for _, op := range test.ops {
+			fields := strings.SplitN(op, " ", 2)
+			ops = append(ops, operation{fields[0], mustRR(t, fields[1])})
+		}
+		if got := riskyChanges("example.org.", ops); !reflect.DeepEqual(got, test.want) {
+			t.Errorf("riskyChanges(%q) = %q, want %q", test.ops, got, test.want)
+		}
+	}
+}
+
+func TestSendUpdateGuards(t *testing.T) {
+	b := useMockBackend(t, nil)
+	ops := []operation{{"replace", mustRR(t, "*.example.org. 300 IN A 192.0.2.1")}}
+
+	_, err := sendUpdate("example.org.", ops)
+	if Kind(err) != ErrValidation || len(b.updates) != 0 {
+		t.Errorf("risky update sent without --force: %v", err)
+	}
+
+	viper.Set("force", true)
+	if _, err := sendUpdate("example.org.", ops); err != nil || len(b.updates) != 1 {
+		t.Errorf("risky update not sent with --force: %v", err)
+	}
+}
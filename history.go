@@ -0,0 +1,216 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"encoding/json"
+	"fmt"
+	"log"
+	"os"
+	"os/user"
+	"path/filepath"
+	"strings"
+	"time"
+
+	"github.com/miekg/dns"
+	"github.com/spf13/viper"
+)
+
+// A historyEntry describes a change made (or attempted) by update-dns.
+type historyEntry struct {
+	Time   time.Time `json:"time"`
+	User   string    `json:"user"`
+	Zone   string    `json:"zone"`
+	Server string    `json:"server"`
+	RRsets []string  `json:"rrsets"`
+	Old    []string  `json:"old"`
+	New    []string  `json:"new"`
+	Result string    `json:"result"`
+}
+
+func historyFile() string {
+	return filepath.Join(os.Getenv("HOME"), ".update-dns", "history")
+}
+
+func currentUser() string {
+	if u, err := user.Current(); err == nil {
+		return u.Username
+	}
+	return os.Getenv("USER")
+}
+
+// applyToRecords returns the records that result from applying ops to the
+// records in before, following the same rules as the server.
+func applyToRecords(before []dns.RR, ops []operation) []dns.RR {
+	records := append([]dns.RR(nil), before...)
+	removeIf := func(match func(dns.RR) bool) {
+		var keep []dns.RR
+		for _, rr := range records {
+			if !match(rr) {
+				keep = append(keep, rr)
+			}
+		}
+		records = keep
+	}
+
+	replaced := make(map[string]bool)
+	for _, op := range ops {
+		key := rrsetKey(op.rr)
+		switch op.action {
+		case "replace":
+			if !replaced[key] {
+				removeIf(func(rr dns.RR) bool { return rrsetKey(rr) == key })
+				replaced[key] = true
+			}
+			records = append(records, op.rr)
+		case "append":
+			if findRecord(records, op.rr) == nil {
+				records = append(records, op.rr)
+			}
+		case "delete":
+			if op.rr.Header().Rrtype == dns.TypeANY {
+				removeIf(func(rr dns.RR) bool {
+					return strings.EqualFold(rr.Header().Name, op.rr.Header().Name)
+				})
+			} else {
+				removeIf(func(rr dns.RR) bool { return rrsetKey(rr) == key })
+			}
+		case "remove":
+			removeIf(func(rr dns.RR) bool { return dns.IsDuplicate(rr, op.rr) })
+		}
+	}
+	return records
+}
+
+func recordStrings(rrs []dns.RR) []string {
+	strs := make([]string, 0, len(rrs))
+	for _, rr := range rrs {
+		strs = append(strs, rr.String())
+	}
+	return strs
+}
+
+func appendHistory(e *journalEntry, ops []operation, result error) error {
+	h := historyEntry{
+		Time:   e.Time,
+		User:   currentUser(),
+		Zone:   e.Zone,
+		Server: e.Server,
+		RRsets: e.RRsets,
+		Old:    recordStrings(e.before),
+		New:    recordStrings(applyToRecords(e.before, ops)),
+		Result: "ok",
+	}
+	if result != nil {
+		h.Result = result.Error()
+		h.New = h.Old
+	}
+
+	data, err := json.Marshal(h)
+	if err != nil {
+		return err
+	}
+
+	if err := os.MkdirAll(filepath.Dir(historyFile()), 0700); err != nil {
+		return err
+	}
+	f, err := os.OpenFile(historyFile(), os.O_WRONLY|os.O_APPEND|os.O_CREATE, 0600)
+	if err != nil {
+		return err
+	}
+	defer f.Close()
+
+	_, err = f.Write(append(data, '\n'))
+	return err
+}
+
+func (h *historyEntry) affects(name string) bool {
+	for _, key := range h.RRsets {
+		if strings.EqualFold(key[:strings.LastIndex(key, "/")], name) {
+			return true
+		}
+	}
+	return false
+}
+
+func printHistoryEntry(h *historyEntry) {
+	fmt.Printf("%s %s %s@%s %s: %s\n", h.Time.Format(time.RFC3339), h.User,
+		h.Zone, h.Server, strings.Join(h.RRsets, ","), h.Result)
+
+	for _, old := range h.Old {
+		if !containsString(h.New, old) {
+			fmt.Printf("  - %s\n", old)
+		}
+	}
+	for _, rr := range h.New {
+		if !containsString(h.Old, rr) {
+			fmt.Printf("  + %s\n", rr)
+		}
+	}
+}
+
+func containsString(strs []string, s string) bool {
+	for _, str := range strs {
+		if str == s {
+			return true
+		}
+	}
+	return false
+}
+
+func history(args []string) int {
+	if len(args) > 1 {
+		log.Print("Usage: update-dns history [name]")
+		return 1
+	}
+
+	data, err := os.ReadFile(historyFile())
+	if err != nil && !os.IsNotExist(err) {
+		log.Printf("Unable to read history: %s", err)
+		return 1
+	}
+
+	var entries []historyEntry
+	for _, line := range strings.Split(string(data), "\n") {
+		if len(strings.TrimSpace(line)) == 0 {
+			continue
+		}
+		var h historyEntry
+		if err := json.Unmarshal([]byte(line), &h); err != nil {
+			log.Printf("Skipping invalid history entry: %s", err)
+			continue
+		}
+		if len(args) == 1 && !h.affects(strings.ToLower(dns.Fqdn(args[0]))) {
+			continue
+		}
+		entries = append(entries, h)
+	}
+
+	if viper.GetString("output") == "json" {
+		if entries == nil {
+			entries = []historyEntry{}
+		}
+		enc := json.NewEncoder(os.Stdout)
+		enc.SetIndent("", "  ")
+		enc.Encode(entries)
+		return 0
+	}
+
+	for i := range entries {
+		printHistoryEntry(&entries[i])
+	}
+	return 0
+}
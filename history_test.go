@@ -0,0 +1,61 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"testing"
+
+	"github.com/miekg/dns"
+)
+
+func TestApplyToRecords(t *testing.T) {
+	a1 := mustRR(t, "www.example.org. 300 IN A 192.0.2.1")
+	a2 := mustRR(t, "www.example.org. 300 IN A 192.0.2.2")
+	a3 := mustRR(t, "www.example.org. 300 IN A 192.0.2.3")
+	txt := mustRR(t, `www.example.org. 300 IN TXT "hello"`)
+	mail := mustRR(t, "mail.example.org. 300 IN A 192.0.2.25")
+	before := []dns.RR{a1, a2, txt, mail}
+
+	rrset := &dns.ANY{Hdr: dns.RR_Header{Name: "www.example.org.", Rrtype: dns.TypeA, Class: dns.ClassANY}}
+	name := &dns.ANY{Hdr: dns.RR_Header{Name: "www.example.org.", Rrtype: dns.TypeANY, Class: dns.ClassANY}}
+
+	tests := []struct {
+		desc string
+		ops  []operation
+		want []dns.RR
+	}{
+		{"replace", []operation{{"replace", a3}, {"replace", a1}}, []dns.RR{txt, mail, a3, a1}},
+		{"append", []operation{{"append", a3}}, []dns.RR{a1, a2, txt, mail, a3}},
+		{"append existing", []operation{{"append", a1}}, before},
+		{"delete RRset", []operation{{"delete", rrset}}, []dns.RR{txt, mail}},
+		{"delete name", []operation{{"delete", name}}, []dns.RR{mail}},
+		{"remove", []operation{{"remove", a2}}, []dns.RR{a1, txt, mail}},
+		{"prerequisite", []operation{{"yxrr", a1}}, before},
+	}
+	for _, test := range tests {
+		got := applyToRecords(before, test.ops)
+		if len(got) != len(test.want) {
+			t.Errorf("%s: got %v, want %v", test.desc, got, test.want)
+			continue
+		}
+		for i := range got {
+			if got[i] != test.want[i] {
+				t.Errorf("%s: got %v, want %v", test.desc, got, test.want)
+				break
+			}
+		}
+	}
+}
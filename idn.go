@@ -0,0 +1,163 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"fmt"
+	"strings"
+	"unicode"
+
+	"github.com/miekg/dns"
+	"github.com/spf13/viper"
+)
+
+// mapNames applies f to the words of s that aren't quoted strings, with
+// their position among the fields of s, keeping everything else
+// (whitespace, quoted strings, comments) as it is.
+func mapNames(s string, f func(int, string) (string, error)) (string, error) {
+	var b strings.Builder
+	var word strings.Builder
+	quoted, escaped := false, false
+	n := 0
+
+	flush := func() error {
+		if word.Len() == 0 {
+			return nil
+		}
+		w, err := f(n, word.String())
+		if err != nil {
+			return err
+		}
+		b.WriteString(w)
+		word.Reset()
+		n++
+		return nil
+	}
+
+	for i, r := range s {
+		switch {
+		case quoted:
+			b.WriteRune(r)
+			if escaped {
+				escaped = false
+			} else if r == '\\' {
+				escaped = true
+			} else if r == '"' {
+				quoted = false
+			}
+		case r == '"':
+			if err := flush(); err != nil {
+				return "", err
+			}
+			b.WriteRune(r)
+			quoted = true
+			n++
+		case r == ';':
+			if err := flush(); err != nil {
+				return "", err
+			}
+			b.WriteString(s[i:])
+			return b.String(), nil
+		case unicode.IsSpace(r):
+			if err := flush(); err != nil {
+				return "", err
+			}
+			b.WriteRune(r)
+		default:
+			word.WriteRune(r)
+		}
+	}
+	if err := flush(); err != nil {
+		return "", err
+	}
+	return b.String(), nil
+}
+
+func isASCII(s string) bool {
+	for _, r := range s {
+		if r > unicode.MaxASCII {
+			return false
+		}
+	}
+	return true
+}
+
+// The positions of the domain names in the data of the types that have
+// them; the data of the other types (e.g. TXT) is left as it is.
+var nameFields = map[uint16][]int{
+	dns.TypeCNAME: {0},
+	dns.TypeDNAME: {0},
+	dns.TypeNS:    {0},
+	dns.TypePTR:   {0},
+	dns.TypeMX:    {1},
+	dns.TypeSRV:   {3},
+	dns.TypeSOA:   {0, 1},
+}
+
+// namePositions returns the positions of the names among the fields of a
+// record: its name, and the names in its data (see nameFields).
+func namePositions(fields []string) map[int]bool {
+	names := map[int]bool{0: true}
+	for i := 1; i < len(fields) && i <= 3; i++ {
+		if rrtype, ok := dns.StringToType[strings.ToUpper(fields[i])]; ok {
+			for _, j := range nameFields[rrtype] {
+				names[i+1+j] = true
+			}
+			break
+		}
+	}
+	return names
+}
+
+// toASCII converts the internationalized names in a record (or a single
+// name) to their punycode (xn--) form: the name of the record, and names in
+// its data such as CNAME and MX targets. Other data, such as the values of
+// TXT records, is left alone.
+func toASCII(record string) (string, error) {
+	if isASCII(record) {
+		return record, nil
+	}
+	names := namePositions(strings.Fields(record))
+	return mapNames(record, func(i int, word string) (string, error) {
+		if isASCII(word) || !names[i] {
+			return word, nil
+		}
+		ascii, err := idnaToASCII(word)
+		if err != nil {
+			return "", fmt.Errorf("invalid internationalized name %s: %s", word, err)
+		}
+		return ascii, nil
+	})
+}
+
+// toUnicode converts the punycode names in a record to Unicode for
+// display, unless --punycode is given. Names that aren't valid IDNs are
+// shown as they are.
+func toUnicode(record string) string {
+	if viper.GetBool("punycode") || !strings.Contains(strings.ToLower(record), "xn--") {
+		return record
+	}
+	res, _ := mapNames(record, func(_ int, word string) (string, error) {
+		if !strings.Contains(strings.ToLower(word), "xn--") {
+			return word, nil
+		}
+		if u, err := idnaToUnicode(word); err == nil {
+			return u, nil
+		}
+		return word, nil
+	})
+	return res
+}
@@ -0,0 +1,52 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//go:build !noidn
+// +build !noidn
+
+package updatedns
+
+import (
+	"testing"
+)
+
+func TestToASCII(t *testing.T) {
+	tests := []struct {
+		record string
+		want   string
+	}{
+		{"www.example.org. A 192.0.2.1", "www.example.org. A 192.0.2.1"},
+		{"bücher.example.", "xn--bcher-kva.example."},
+		{"bücher.example. 300 IN A 192.0.2.1", "xn--bcher-kva.example. 300 IN A 192.0.2.1"},
+		{"www CNAME bücher.example.", "www CNAME xn--bcher-kva.example."},
+		{"www 300 IN MX 10 bücher.example.", "www 300 IN MX 10 xn--bcher-kva.example."},
+		{"_sip._tcp SRV 0 5 5060 bücher.example.", "_sip._tcp SRV 0 5 5060 xn--bcher-kva.example."},
+		// Other data, quoted strings and comments are left alone
+		{"www TXT bücher", "www TXT bücher"},
+		{`bücher TXT "bücher" ; bücher`, `xn--bcher-kva TXT "bücher" ; bücher`},
+	}
+	for _, test := range tests {
+		got, err := toASCII(test.record)
+		if err != nil {
+			t.Errorf("toASCII(%q): %v", test.record, err)
+		} else if got != test.want {
+			t.Errorf("toASCII(%q) = %q, want %q", test.record, got, test.want)
+		}
+	}
+
+	if got, err := toASCII("\u0301b.example. A 192.0.2.1"); err == nil {
+		t.Errorf("toASCII accepted a label starting with a combining mark: %q", got)
+	}
+}
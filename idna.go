@@ -0,0 +1,37 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//go:build !noidn
+// +build !noidn
+
+package updatedns
+
+import (
+	"golang.org/x/net/idna"
+)
+
+// The IDNA profile for converting names. It is the lookup profile, but
+// allows the underscores and wildcards used in DNS names that aren't host
+// names.
+var idnaProfile = idna.New(idna.MapForLookup(), idna.BidiRule(),
+	idna.Transitional(false), idna.StrictDomainName(false))
+
+func idnaToASCII(name string) (string, error) {
+	return idnaProfile.ToASCII(name)
+}
+
+func idnaToUnicode(name string) (string, error) {
+	return idna.Display.ToUnicode(name)
+}
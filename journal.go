@@ -0,0 +1,308 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"encoding/json"
+	"fmt"
+	"log"
+	"os"
+	"path/filepath"
+	"strings"
+	"time"
+
+	"github.com/miekg/dns"
+	"github.com/spf13/viper"
+)
+
+// A journalEntry records the state of the RRsets changed by an update
+// before it was sent, so the update can be undone.
+type journalEntry struct {
+	Time   time.Time `json:"time"`
+	Zone   string    `json:"zone"`
+	Server string    `json:"server"`
+	RRsets []string  `json:"rrsets"`
+	Before []string  `json:"before"`
+
+	before []dns.RR
+}
+
+// Set while undoing, so the undo itself isn't journaled
+var journalDisabled bool
+
+func journalFile() string {
+	return filepath.Join(os.Getenv("HOME"), ".update-dns", "journal")
+}
+
+// currentRRset returns the records in the RRset identified by key (as
+// returned by rrsetKey). A key with type ANY covers all records at the name.
+func currentRRset(key string) ([]dns.RR, error) {
+	idx := strings.LastIndex(key, "/")
+	name, rrtype := key[:idx], dns.StringToType[key[idx+1:]]
+	if rrtype == dns.TypeANY {
+		return recordsAt(name)
+	}
+
+	rrs, err := lookup(name, rrtype)
+	if err != nil {
+		return nil, err
+	}
+
+	var res []dns.RR
+	for _, rr := range rrs {
+		if rr.Header().Rrtype == rrtype && strings.EqualFold(rr.Header().Name, name) {
+			res = append(res, rr)
+		}
+	}
+	return res, nil
+}
+
+// journalState looks up the current records in the RRsets changed by ops.
+// It returns nil if both the journal and history are disabled, or the
+// lookup fails.
+func journalState(zone string, ops []operation) *journalEntry {
+	if !viper.GetBool("journal") && !viper.GetBool("history") {
+		return nil
+	}
+
+	entry, err := captureState(zone, ops)
+	if err != nil {
+		log.Printf("Unable to record %s in the journal: %s", zone, err)
+		return nil
+	}
+	return entry
+}
+
+// captureState looks up the current records in the RRsets changed by ops,
+// so the update can be undone. It returns nil if ops change nothing. From
+// transfer-threshold RRsets on, the records are taken from a single zone
+// transfer instead of a query per RRset, if the server allows it.
+func captureState(zone string, ops []operation) (*journalEntry, error) {
+	entry := &journalEntry{
+		Time:   time.Now(),
+		Zone:   zone,
+		Server: setting(zone, "server"),
+	}
+	seen := make(map[string]bool)
+	for _, op := range ops {
+		switch op.action {
+		case "replace", "append", "delete", "remove":
+		default:
+			continue
+		}
+
+		key := rrsetKey(op.rr)
+		if !seen[key] {
+			seen[key] = true
+			entry.RRsets = append(entry.RRsets, key)
+		}
+	}
+	if len(entry.RRsets) == 0 {
+		return nil, nil
+	}
+
+	var zoneRRs []dns.RR
+	if threshold := viper.GetInt("transfer-threshold"); threshold > 0 && len(entry.RRsets) >= threshold {
+		var err error
+		if zoneRRs, err = transfer(zone); err != nil {
+			debugf("Zone transfer of %s failed (%s); querying each RRset", zone, err)
+			zoneRRs = nil
+		}
+	}
+
+	for _, key := range entry.RRsets {
+		var rrs []dns.RR
+		if zoneRRs != nil {
+			rrs = rrsetFrom(zoneRRs, key)
+		} else {
+			var err error
+			if rrs, err = currentRRset(key); err != nil {
+				return nil, fmt.Errorf("%s: %s", key, err)
+			}
+		}
+		for _, rr := range rrs {
+			entry.Before = append(entry.Before, rr.String())
+		}
+		entry.before = append(entry.before, rrs...)
+	}
+	return entry, nil
+}
+
+// rrsetFrom returns the records of rrs in the RRset identified by key, with
+// the same records as currentRRset would return.
+func rrsetFrom(rrs []dns.RR, key string) []dns.RR {
+	idx := strings.LastIndex(key, "/")
+	name, rrtype := key[:idx], dns.StringToType[key[idx+1:]]
+
+	var res []dns.RR
+	for _, rr := range rrs {
+		if !strings.EqualFold(rr.Header().Name, name) {
+			continue
+		}
+		switch t := rr.Header().Rrtype; {
+		case t == rrtype:
+		case rrtype != dns.TypeANY:
+			continue
+		case t == dns.TypeRRSIG, t == dns.TypeNSEC, t == dns.TypeNSEC3, t == dns.TypeNSEC3PARAM:
+			continue
+		}
+		res = append(res, rr)
+	}
+	return res
+}
+
+func readJournal() ([]string, error) {
+	data, err := os.ReadFile(journalFile())
+	if os.IsNotExist(err) {
+		return nil, nil
+	} else if err != nil {
+		return nil, err
+	}
+
+	var lines []string
+	for _, line := range strings.Split(string(data), "\n") {
+		if len(strings.TrimSpace(line)) > 0 {
+			lines = append(lines, line)
+		}
+	}
+	return lines, nil
+}
+
+// writeJournal writes the journal, keeping only the newest journal-size
+// entries.
+func writeJournal(lines []string) error {
+	if max := viper.GetInt("journal-size"); len(lines) > max {
+		lines = lines[len(lines)-max:]
+	}
+
+	data := ""
+	if len(lines) > 0 {
+		data = strings.Join(lines, "\n") + "\n"
+	}
+	if err := os.MkdirAll(filepath.Dir(journalFile()), 0700); err != nil {
+		return err
+	}
+	return os.WriteFile(journalFile(), []byte(data), 0600)
+}
+
+// record saves the outcome of an update in the history and, if it
+// succeeded, in the journal.
+func (e *journalEntry) record(ops []operation, result error) {
+	if e == nil {
+		return
+	}
+
+	if viper.GetBool("history") {
+		if err := appendHistory(e, ops, result); err != nil {
+			log.Printf("Unable to write history: %s", err)
+		}
+	}
+
+	if result == nil && viper.GetBool("journal") && !journalDisabled {
+		if err := e.save(); err != nil {
+			log.Printf("Unable to write journal: %s", err)
+		}
+	}
+}
+
+func (e *journalEntry) save() error {
+	data, err := json.Marshal(e)
+	if err != nil {
+		return err
+	}
+
+	lines, err := readJournal()
+	if err != nil {
+		return err
+	}
+	return writeJournal(append(lines, string(data)))
+}
+
+// undoOps returns the operations restoring the RRsets in e to their
+// recorded state.
+func (e *journalEntry) undoOps() ([]operation, error) {
+	var ops []operation
+	for _, key := range e.RRsets {
+		idx := strings.LastIndex(key, "/")
+		rr, err := parseRecord(key[:idx]+" "+key[idx+1:], "delete")
+		if key[idx+1:] == "ANY" {
+			rr, err = parseRecord(key[:idx], "delete")
+		}
+		if err != nil {
+			return nil, err
+		}
+		ops = append(ops, operation{"delete", rr})
+	}
+
+	for _, record := range e.Before {
+		rr, err := dns.NewRR(record)
+		if err != nil {
+			return nil, fmt.Errorf("invalid journal record '%s': %s", record, err)
+		}
+		ops = append(ops, operation{"append", rr})
+	}
+	return ops, nil
+}
+
+func undo(args []string) int {
+	if len(args) != 0 {
+		log.Print("Usage: update-dns undo")
+		return 1
+	}
+
+	lines, err := readJournal()
+	if err != nil {
+		log.Printf("Unable to read journal: %s", err)
+		return 1
+	} else if len(lines) == 0 {
+		infof("Nothing to undo")
+		return 1
+	}
+
+	var entry journalEntry
+	if err := json.Unmarshal([]byte(lines[len(lines)-1]), &entry); err != nil {
+		log.Printf("Invalid journal entry: %s", err)
+		return 1
+	}
+
+	ops, err := entry.undoOps()
+	if err != nil {
+		log.Print(err)
+		return 1
+	}
+
+	if entry.Server != setting(entry.Zone, "server") {
+		infof("Sending undo to %s, which the change was made on", entry.Server)
+		overrideSetting("server", entry.Server)
+	}
+
+	infof("Undoing change to %s made at %s", strings.Join(entry.RRsets, ", "),
+		entry.Time.Format(time.RFC3339))
+
+	journalDisabled = true
+	if err := update(entry.Zone, ops); err != nil {
+		log.Printf("Unable to send update: %s", err)
+		return exitCode(err)
+	}
+
+	if err := writeJournal(lines[:len(lines)-1]); err != nil {
+		log.Printf("Unable to update journal: %s", err)
+		return 1
+	}
+
+	infof("Undo successful")
+	return 0
+}
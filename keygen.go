@@ -0,0 +1,79 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"crypto/rand"
+	"encoding/base64"
+	"fmt"
+	"log"
+	"strings"
+
+	"github.com/miekg/dns"
+	"github.com/spf13/viper"
+)
+
+// The digest sizes of the TSIG algorithms, which are also the recommended
+// key sizes.
+var tsigKeySizes = map[string]int{
+	dns.HmacMD5:    16,
+	dns.HmacSHA1:   20,
+	dns.HmacSHA224: 28,
+	dns.HmacSHA256: 32,
+	dns.HmacSHA384: 48,
+	dns.HmacSHA512: 64,
+}
+
+func keygen(args []string) int {
+	if len(args) != 1 {
+		log.Print("Usage: update-dns keygen [--algorithm <alg>] [--write] <name>")
+		return 1
+	}
+	name := dns.Fqdn(args[0])
+
+	alg, ok := lookupTsigAlgorithm(viper.GetString("tsig-algorithm"))
+	if !ok {
+		log.Printf("Unknown algorithm: %s", viper.GetString("tsig-algorithm"))
+		return 1
+	}
+	algName := strings.TrimSuffix(strings.TrimSuffix(alg, "."), ".sig-alg.reg.int")
+
+	key := make([]byte, tsigKeySizes[alg])
+	if _, err := rand.Read(key); err != nil {
+		log.Printf("Unable to generate key: %s", err)
+		return 1
+	}
+	secret := base64.StdEncoding.EncodeToString(key)
+
+	fmt.Printf("# named.conf\nkey \"%s\" {\n\talgorithm %s;\n\tsecret \"%s\";\n};\n\n",
+		strings.TrimSuffix(name, "."), algName, secret)
+	fmt.Printf("# knot.conf\nkey:\n  - id: %s\n    algorithm: %s\n    secret: %s\n",
+		name, algName, secret)
+
+	if viper.GetBool("keygen.write") {
+		err := setConfigValues(userConfigFile(), [][2]string{
+			{"tsig-name", name},
+			{"tsig-algorithm", algName},
+			{"tsig-secret", secret},
+		})
+		if err != nil {
+			log.Printf("Unable to write config: %s", err)
+			return 1
+		}
+		infof("Wrote key to %s", userConfigFile())
+	}
+	return 0
+}
@@ -0,0 +1,234 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"fmt"
+	"log"
+	"os"
+	"path"
+	"strings"
+	"time"
+
+	"github.com/miekg/dns"
+	"github.com/spf13/viper"
+)
+
+// transfer performs a TSIG-signed AXFR of zone from the configured server.
+// The SOA record that terminates the transfer is not included in the result.
+func transfer(zone string) ([]dns.RR, error) {
+	if t, ok := dnsBackend.(Transferer); ok {
+		rrs, err := t.Transfer(dns.Fqdn(zone), serverFor(zone))
+		if err == nil && len(rrs) > 1 {
+			if _, ok := rrs[len(rrs)-1].(*dns.SOA); ok {
+				rrs = rrs[:len(rrs)-1]
+			}
+		}
+		return rrs, err
+	}
+
+	t := new(dns.Transfer)
+	t.TsigSecret = make(map[string]string)
+	t.TsigSecret[tsigName(zone)] = tsigSecret(zone)
+
+	m := new(dns.Msg)
+	m.SetAxfr(dns.Fqdn(zone))
+	m.SetTsig(tsigName(zone), tsigAlgorithm(zone), 300, time.Now().Unix())
+
+	dnsDebugf("Requesting AXFR of %s from %s", dns.Fqdn(zone), serverFor(zone))
+	ch, err := t.In(m, serverFor(zone))
+	if err != nil {
+		return nil, err
+	}
+
+	p := newProgress("Transferring "+dns.Fqdn(zone), "records", 0)
+	defer p.finish()
+
+	var rrs []dns.RR
+	for env := range ch {
+		if env.Error != nil {
+			return nil, env.Error
+		}
+		dnsDebugf("Received AXFR message with %d records", len(env.RR))
+		rrs = append(rrs, env.RR...)
+		p.add(len(env.RR))
+	}
+
+	if len(rrs) > 1 {
+		if _, ok := rrs[len(rrs)-1].(*dns.SOA); ok {
+			rrs = rrs[:len(rrs)-1]
+		}
+	}
+	return rrs, nil
+}
+
+// filterRecords returns the records matching the name glob and record type
+// given on the command line.
+func filterRecords(rrs []dns.RR) ([]dns.RR, error) {
+	glob := strings.ToLower(viper.GetString("name"))
+	if len(glob) > 0 {
+		glob = dns.Fqdn(glob)
+	}
+
+	var rrtype uint16
+	if len(viper.GetString("type")) > 0 {
+		t, err := parseType(viper.GetString("type"))
+		if err != nil {
+			return nil, err
+		}
+		rrtype = t
+	}
+
+	var res []dns.RR
+	for _, rr := range rrs {
+		if rrtype != 0 && rr.Header().Rrtype != rrtype {
+			continue
+		}
+		if len(glob) > 0 {
+			match, err := path.Match(glob, strings.ToLower(rr.Header().Name))
+			if err != nil {
+				return nil, err
+			}
+			if !match {
+				continue
+			}
+		}
+		res = append(res, rr)
+	}
+	return res, nil
+}
+
+func list(args []string) int {
+	if len(args) != 1 {
+		log.Print("Usage: update-dns list <zone>")
+		return 1
+	}
+
+	rrs, err := transfer(args[0])
+	if err != nil {
+		log.Printf("Zone transfer failed: %s", err)
+		return 1
+	}
+
+	rrs, err = filterRecords(rrs)
+	if err != nil {
+		log.Printf("Invalid filter: %s", err)
+		return 1
+	}
+
+	printRecords(os.Stdout, rrs)
+	return 0
+}
+
+func prune(args []string) int {
+	if len(args) != 1 {
+		log.Print("Usage: update-dns prune [--name <glob>] [--older-than <age>] [-t <type>] <zone>")
+		return 1
+	}
+	zone := dns.Fqdn(args[0])
+
+	// Without a name or age we would prune the whole zone
+	olderThan := viper.GetString("prune.older-than")
+	if len(viper.GetString("name")) == 0 && len(olderThan) == 0 {
+		log.Print("Refusing to prune without --name or --older-than")
+		return 1
+	}
+	var age time.Duration
+	if len(olderThan) > 0 {
+		if !registryEnabled() {
+			log.Print("--older-than needs the ownership records of registry.enabled")
+			return 1
+		}
+		var err error
+		if age, err = parseAge(olderThan); err != nil {
+			log.Print(err)
+			return 1
+		}
+	}
+
+	live, err := transfer(zone)
+	if err != nil {
+		log.Printf("Zone transfer failed: %s", err)
+		return 1
+	}
+
+	rrs, err := filterRecords(live)
+	if err != nil {
+		log.Printf("Invalid filter: %s", err)
+		return 1
+	}
+
+	var stale map[string]bool
+	if len(olderThan) > 0 {
+		stale = staleNames(live, age)
+	}
+
+	var ops []operation
+	var removed []dns.RR
+	pruned := make(map[string]bool)
+	for _, rr := range rrs {
+		name := strings.ToLower(rr.Header().Name)
+		if !managedRecord(rr) || name == strings.ToLower(zone) || (stale != nil && !stale[name]) {
+			continue
+		}
+		removed = append(removed, rr)
+		ops = append(ops, operation{"remove", rr})
+		pruned[name] = true
+	}
+	// The ownership records of the names left without records go too
+	prefix := strings.ToLower(viper.GetString("registry.prefix"))
+	for _, rr := range live {
+		if _, ok := ownershipTime(rr); !ok {
+			continue
+		}
+		name := strings.TrimPrefix(strings.ToLower(rr.Header().Name), prefix)
+		if pruned[name] && !keepsRecords(live, removed, name) {
+			removed = append(removed, rr)
+			ops = append(ops, operation{"remove", rr})
+		}
+	}
+	for _, line := range formatChanges(os.Stdout, removed, nil) {
+		fmt.Println(line)
+	}
+
+	if len(ops) == 0 {
+		log.Print("No matching records")
+		return 0
+	}
+
+	if soa, ok := live[0].(*dns.SOA); ok {
+		ops = append([]operation{{"yxrr", soa}}, ops...)
+	}
+	err = update(zone, ops)
+	if err != nil {
+		log.Printf("Unable to send update: %s", err)
+		return exitCode(err)
+	}
+
+	infof("Pruned %d records from %s", len(removed), zone)
+	return 0
+}
+
+// keepsRecords reports whether name still has managed records in live once
+// removed are deleted.
+func keepsRecords(live, removed []dns.RR, name string) bool {
+	for _, rr := range live {
+		if managedRecord(rr) && strings.EqualFold(rr.Header().Name, name) && findRecord(removed, rr) == nil {
+			return true
+		}
+	}
+	return false
+}
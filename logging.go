@@ -0,0 +1,217 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"bytes"
+	"encoding/binary"
+	"fmt"
+	"log"
+	"net"
+	"os"
+	"sort"
+	"strings"
+
+	"github.com/spf13/viper"
+)
+
+// Log levels. Errors and warnings are always logged with log.Print; the
+// other levels only with infof, debugf and tracef.
+const (
+	levelWarn  = iota // errors and warnings only (-q)
+	levelInfo         // also what was done (the default)
+	levelDebug        // also the messages sent (-v)
+	levelTrace        // also the answers received (-vv)
+)
+
+// Errors and warnings are both logged with log.Print, so there is no
+// separate error level.
+var logLevels = map[string]int{
+	"warn":    levelWarn,
+	"warning": levelWarn,
+	"info":    levelInfo,
+	"debug":   levelDebug,
+	"trace":   levelTrace,
+}
+
+var logLevel = levelInfo
+
+// setLogLevel sets the log level from --log-level, or from -q and the
+// number of -v flags.
+func setLogLevel(name string, quiet bool, verbose int) error {
+	switch {
+	case len(name) > 0:
+		level, ok := logLevels[strings.ToLower(name)]
+		if !ok {
+			return fmt.Errorf("unknown log level %s (use warn, info, debug or trace)", name)
+		}
+		logLevel = level
+	case quiet:
+		logLevel = levelWarn
+	default:
+		logLevel = levelInfo + verbose
+	}
+	return nil
+}
+
+// Syslog priorities of the log levels
+const (
+	priorityWarning = 4
+	priorityInfo    = 6
+	priorityDebug   = 7
+)
+
+// A logBackend sends log messages somewhere other than stderr.
+type logBackend interface {
+	send(priority int, msg string, fields map[string]string)
+}
+
+// The backend selected with --log, or nil for stderr.
+var backend logBackend
+
+// A backendWriter passes the messages logged with the log package, which
+// are errors and warnings, to the backend.
+type backendWriter struct{}
+
+func (backendWriter) Write(p []byte) (int, error) {
+	backend.send(priorityWarning, strings.TrimSuffix(string(p), "\n"), nil)
+	return len(p), nil
+}
+
+// setLogBackend selects where log messages go: stderr, journald or syslog.
+func setLogBackend(name string) error {
+	switch strings.ToLower(name) {
+	case "", "stderr":
+		return nil
+	case "journald", "journal":
+		conn, err := net.Dial("unixgram", "/run/systemd/journal/socket")
+		if err != nil {
+			return fmt.Errorf("unable to connect to journald: %s", err)
+		}
+		backend = &journald{conn}
+	case "syslog":
+		b, err := newSyslogBackend()
+		if err != nil {
+			return fmt.Errorf("unable to connect to syslog: %s", err)
+		}
+		backend = b
+	default:
+		return fmt.Errorf("unknown log backend %s (use stderr, journald or syslog)", name)
+	}
+	log.SetFlags(0)
+	log.SetOutput(backendWriter{})
+	return nil
+}
+
+// journald sends log entries with the native journal protocol, so the
+// fields of update results are kept as journal fields.
+type journald struct {
+	conn net.Conn
+}
+
+func (j *journald) send(priority int, msg string, fields map[string]string) {
+	var buf bytes.Buffer
+	field := func(key, value string) {
+		if strings.Contains(value, "\n") {
+			// Values with newlines are written with their length
+			buf.WriteString(key + "\n")
+			binary.Write(&buf, binary.LittleEndian, uint64(len(value)))
+			buf.WriteString(value + "\n")
+		} else {
+			buf.WriteString(key + "=" + value + "\n")
+		}
+	}
+	field("MESSAGE", msg)
+	field("PRIORITY", fmt.Sprint(priority))
+	field("SYSLOG_IDENTIFIER", "update-dns")
+	keys := make([]string, 0, len(fields))
+	for key := range fields {
+		keys = append(keys, key)
+	}
+	sort.Strings(keys)
+	for _, key := range keys {
+		field("UPDATE_DNS_"+strings.ToUpper(key), fields[key])
+	}
+
+	if _, err := j.conn.Write(buf.Bytes()); err != nil {
+		fmt.Fprintf(os.Stderr, "%s\n", msg)
+	}
+}
+
+// output logs a message at the given priority, on the backend if one is
+// selected.
+func output(priority int, format string, args ...interface{}) {
+	if backend != nil {
+		backend.send(priority, fmt.Sprintf(format, args...), nil)
+	} else {
+		log.Printf(format, args...)
+	}
+}
+
+func infof(format string, args ...interface{}) {
+	if logLevel >= levelInfo {
+		output(priorityInfo, format, args...)
+	}
+}
+
+func debugf(format string, args ...interface{}) {
+	if logLevel >= levelDebug {
+		output(priorityDebug, format, args...)
+	}
+}
+
+func tracef(format string, args ...interface{}) {
+	if logLevel >= levelTrace {
+		output(priorityDebug, format, args...)
+	}
+}
+
+// dnsDebugf logs the details of the DNS exchanges (transports, sizes,
+// round-trip times, retries over TCP and zone transfer messages). These are
+// only logged with --debug-dns, whatever the log level, so they don't drown
+// out the messages logged with -v and -vv.
+func dnsDebugf(format string, args ...interface{}) {
+	if viper.GetBool("debug-dns") {
+		output(priorityDebug, "dns: "+format, args...)
+	}
+}
+
+// logResult logs an entry with the fields of an update result when
+// logging to journald or syslog, so the results can be searched by zone,
+// name, type and result.
+func logResult(res *updateResult, names, types []string) {
+	if backend == nil || logLevel < levelInfo {
+		return
+	}
+	fields := map[string]string{
+		"zone":   res.Zone,
+		"server": res.Server,
+		"name":   strings.Join(names, " "),
+		"type":   strings.Join(types, " "),
+		"result": res.Status,
+	}
+	if len(res.Rcode) > 0 {
+		fields["rcode"] = res.Rcode
+	}
+	if len(res.Error) > 0 {
+		fields["error"] = res.Error
+	}
+	priority := priorityInfo
+	if res.Status == "failed" {
+		priority = priorityWarning
+	}
+	backend.send(priority, fmt.Sprintf("Update of %s: %s", res.Zone, res.Status), fields)
+}
@@ -0,0 +1,127 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"bytes"
+	"fmt"
+	"log"
+	"net/http"
+	"os"
+	"path/filepath"
+	"sort"
+	"strings"
+	"time"
+
+	"github.com/spf13/viper"
+)
+
+// When the run started, for the duration metric
+var startTime = time.Now()
+
+// metricsEnabled reports whether metrics are written at the end of the run.
+func metricsEnabled() bool {
+	return len(viper.GetString("metrics.textfile")) > 0 || len(viper.GetString("metrics.pushgateway")) > 0
+}
+
+// formatMetrics returns the metrics of the run in the Prometheus text
+// format: the number of updates per zone and result, and the duration,
+// time and exit status of the run.
+func formatMetrics(command string, exitcode int) []byte {
+	counts := make(map[string]int)
+	for _, res := range updateResults {
+		counts[res.Zone+"\x00"+res.Status]++
+	}
+	keys := make([]string, 0, len(counts))
+	for key := range counts {
+		keys = append(keys, key)
+	}
+	sort.Strings(keys)
+
+	var buf bytes.Buffer
+	fmt.Fprintln(&buf, "# HELP update_dns_updates Updates in the last run, by zone and result (sent, unchanged, dry-run or failed).")
+	fmt.Fprintln(&buf, "# TYPE update_dns_updates gauge")
+	for _, key := range keys {
+		parts := strings.SplitN(key, "\x00", 2)
+		fmt.Fprintf(&buf, "update_dns_updates{command=%q,zone=%q,result=%q} %d\n", command, parts[0], parts[1], counts[key])
+	}
+	fmt.Fprintln(&buf, "# HELP update_dns_last_run_duration_seconds How long the last run took.")
+	fmt.Fprintln(&buf, "# TYPE update_dns_last_run_duration_seconds gauge")
+	fmt.Fprintf(&buf, "update_dns_last_run_duration_seconds{command=%q} %f\n", command, time.Since(startTime).Seconds())
+	fmt.Fprintln(&buf, "# HELP update_dns_last_run_timestamp_seconds When the last run finished.")
+	fmt.Fprintln(&buf, "# TYPE update_dns_last_run_timestamp_seconds gauge")
+	fmt.Fprintf(&buf, "update_dns_last_run_timestamp_seconds{command=%q} %d\n", command, time.Now().Unix())
+	fmt.Fprintln(&buf, "# HELP update_dns_last_run_exit_code The exit status of the last run.")
+	fmt.Fprintln(&buf, "# TYPE update_dns_last_run_exit_code gauge")
+	fmt.Fprintf(&buf, "update_dns_last_run_exit_code{command=%q} %d\n", command, exitcode)
+	return buf.Bytes()
+}
+
+// writeMetrics writes the metrics of the run to the node_exporter textfile
+// given as metrics.textfile, and pushes them to the Prometheus pushgateway
+// at metrics.pushgateway.
+func writeMetrics(command string, exitcode int) {
+	if !metricsEnabled() {
+		return
+	}
+	data := formatMetrics(command, exitcode)
+
+	if file := viper.GetString("metrics.textfile"); len(file) > 0 {
+		// Written to a temporary file first, so node_exporter never
+		// reads a partial file
+		tmp, err := os.CreateTemp(filepath.Dir(file), ".update-dns-metrics")
+		if err == nil {
+			_, err = tmp.Write(data)
+			if cerr := tmp.Close(); err == nil {
+				err = cerr
+			}
+			if err == nil {
+				err = os.Chmod(tmp.Name(), 0644)
+			}
+			if err == nil {
+				err = os.Rename(tmp.Name(), file)
+			}
+			if err != nil {
+				os.Remove(tmp.Name())
+			}
+		}
+		if err != nil {
+			log.Printf("Unable to write metrics to %s: %s", file, err)
+		}
+	}
+
+	if url := viper.GetString("metrics.pushgateway"); len(url) > 0 {
+		url = strings.TrimSuffix(url, "/") + "/metrics/job/" + viper.GetString("metrics.job")
+		if instance := viper.GetString("metrics.instance"); len(instance) > 0 {
+			url += "/instance/" + instance
+		}
+		req, err := http.NewRequest(http.MethodPut, url, bytes.NewReader(data))
+		if err == nil {
+			req.Header.Set("Content-Type", "text/plain; version=0.0.4")
+			var resp *http.Response
+			client := &http.Client{Timeout: 10 * time.Second}
+			if resp, err = client.Do(req); err == nil {
+				resp.Body.Close()
+				if resp.StatusCode/100 != 2 {
+					err = fmt.Errorf("%s", resp.Status)
+				}
+			}
+		}
+		if err != nil {
+			log.Printf("Unable to push metrics to %s: %s", url, err)
+		}
+	}
+}
@@ -0,0 +1,35 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//go:build nokeyring
+// +build nokeyring
+
+package updatedns
+
+import (
+	"errors"
+)
+
+// Built with the nokeyring tag, without github.com/zalando/go-keyring:
+// tsig-secret-keyring and config set-secret don't work.
+var errNoKeyring = errors.New("built without keyring support (nokeyring)")
+
+func keyringGet(user string) (string, error) {
+	return "", errNoKeyring
+}
+
+func keyringSet(user, secret string) error {
+	return errNoKeyring
+}
@@ -0,0 +1,358 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"bufio"
+	"fmt"
+	"io"
+	"log"
+	"net"
+	"os"
+	"strconv"
+	"strings"
+
+	"github.com/miekg/dns"
+)
+
+// printNsupdate writes ops as nsupdate commands for sending them to server
+// as an update of zone.
+func printNsupdate(w io.Writer, server, zone string, ops []operation) {
+	if host, port, err := net.SplitHostPort(server); err == nil {
+		fmt.Fprintf(w, "server %s %s\n", host, port)
+	}
+	fmt.Fprintf(w, "zone %s\n", zone)
+
+	removed := make(map[string]bool)
+	for _, op := range ops {
+		hdr := op.rr.Header()
+		rrtype := dns.TypeToString[hdr.Rrtype]
+		switch op.action {
+		case "replace":
+			if !removed[rrsetKey(op.rr)] {
+				fmt.Fprintf(w, "update delete %s %s\n", hdr.Name, rrtype)
+				removed[rrsetKey(op.rr)] = true
+			}
+			fmt.Fprintf(w, "update add %s\n", op.rr)
+		case "append":
+			fmt.Fprintf(w, "update add %s\n", op.rr)
+		case "delete":
+			if hdr.Rrtype == dns.TypeANY {
+				fmt.Fprintf(w, "update delete %s\n", hdr.Name)
+			} else {
+				fmt.Fprintf(w, "update delete %s %s\n", hdr.Name, rrtype)
+			}
+		case "remove":
+			fmt.Fprintf(w, "update delete %s\n", op.rr)
+		case "yxdomain", "nxdomain":
+			fmt.Fprintf(w, "prereq %s %s\n", op.action, hdr.Name)
+		case "yxrrset", "nxrrset":
+			fmt.Fprintf(w, "prereq %s %s %s\n", op.action, hdr.Name, rrtype)
+		case "yxrr":
+			fmt.Fprintf(w, "prereq yxrrset %s\n", op.rr)
+		}
+	}
+	fmt.Fprintln(w, "send")
+}
+
+// An nsupdateSession interprets the nsupdate command language, collecting
+// operations until they are sent.
+type nsupdateSession struct {
+	zone   string
+	ttl    string
+	ops    []operation
+	answer *dns.Msg
+	out    io.Writer
+
+	// In interactive sessions, updates are only sent by an explicit 'send'
+	interactive bool
+}
+
+// skipWords returns line with the first n words removed.
+func skipWords(line string, n int) string {
+	for i := 0; i < n; i++ {
+		line = strings.TrimSpace(line)
+		idx := strings.IndexAny(line, " \t")
+		if idx < 0 {
+			return ""
+		}
+		line = line[idx:]
+	}
+	return strings.TrimSpace(line)
+}
+
+// stripClass removes an optional leading class from args. Only the IN class
+// is supported for updates.
+func stripClass(args []string) ([]string, error) {
+	if len(args) == 0 {
+		return args, nil
+	}
+	class, ok := dns.StringToClass[strings.ToUpper(args[0])]
+	if !ok {
+		return args, nil
+	}
+	if class != dns.ClassINET {
+		return nil, fmt.Errorf("unsupported class: %s", args[0])
+	}
+	return args[1:], nil
+}
+
+func (s *nsupdateSession) parseAdd(record string) (operation, error) {
+	if len(s.ttl) > 0 {
+		record = "$TTL " + s.ttl + "\n" + record
+	}
+	rr, err := parseRecord(record, "append")
+	return operation{"append", rr}, err
+}
+
+// parseDelete parses 'delete name [ttl] [class] [type [data...]]'.
+func (s *nsupdateSession) parseDelete(args []string) (operation, error) {
+	if len(args) == 0 {
+		return operation{}, fmt.Errorf("missing name")
+	}
+
+	name, rest := args[0], args[1:]
+	if len(rest) > 0 {
+		if _, err := strconv.ParseUint(rest[0], 10, 32); err == nil {
+			rest = rest[1:]
+		}
+	}
+	if len(rest) > 0 {
+		if _, ok := dns.StringToClass[strings.ToUpper(rest[0])]; ok {
+			rest = rest[1:]
+		}
+	}
+
+	// Without rdata, the whole RRset (or name) is deleted
+	action := "remove"
+	if len(rest) < 2 {
+		action = "delete"
+	}
+
+	rr, err := parseRecord(strings.TrimSpace(name+" "+strings.Join(rest, " ")), "delete")
+	return operation{action, rr}, err
+}
+
+// parsePrereq parses the nxdomain, yxdomain, nxrrset and yxrrset
+// prerequisites.
+func (s *nsupdateSession) parsePrereq(kind string, args []string) (operation, error) {
+	if len(args) == 0 {
+		return operation{}, fmt.Errorf("missing name")
+	}
+
+	name := args[0]
+	rest, err := stripClass(args[1:])
+	if err != nil {
+		return operation{}, err
+	}
+
+	switch kind {
+	case "nxdomain", "yxdomain":
+		if len(rest) > 0 {
+			return operation{}, fmt.Errorf("unexpected data after name")
+		}
+		rr, err := parseRecord(name, "delete")
+		return operation{kind, rr}, err
+	}
+
+	if len(rest) == 0 {
+		return operation{}, fmt.Errorf("missing type")
+	}
+	if kind == "yxrrset" && len(rest) > 1 {
+		kind = "yxrr"
+	}
+	// As for deletions, a type without data is fine
+	rr, err := parseRecord(name+" "+strings.Join(rest, " "), "delete")
+	if err != nil {
+		return operation{}, err
+	}
+	return operation{kind, rr}, nil
+}
+
+func (s *nsupdateSession) currentZone() string {
+	if len(s.zone) > 0 {
+		return s.zone
+	}
+	return getZone(s.ops[0].rr.Header().Name)
+}
+
+func (s *nsupdateSession) send() error {
+	if len(s.ops) == 0 {
+		return nil
+	}
+
+	zone := s.currentZone()
+	if err := checkGuards(zone, s.ops, false); err != nil {
+		s.ops = nil
+		return err
+	}
+	r, err := sendUpdate(zone, s.ops)
+	s.answer = r
+	s.ops = nil
+	if err != nil {
+		return err
+	}
+	infof("Updated zone %s", zone)
+	return nil
+}
+
+// execute runs a single line of nsupdate input. It returns true when the
+// session should end.
+func (s *nsupdateSession) execute(line string) (bool, error) {
+	line = strings.TrimSpace(line)
+	if len(line) == 0 {
+		if s.interactive {
+			return false, nil
+		}
+		return false, s.send()
+	}
+	if strings.HasPrefix(line, ";") || strings.HasPrefix(line, "#") {
+		return false, nil
+	}
+
+	fields := strings.Fields(line)
+	cmd, args, words := strings.ToLower(fields[0]), fields[1:], 1
+	if (cmd == "update" || cmd == "prereq") && len(args) > 0 {
+		cmd, args, words = strings.ToLower(args[0]), args[1:], 2
+	}
+
+	var op operation
+	var err error
+
+	switch cmd {
+	case "add":
+		op, err = s.parseAdd(skipWords(line, words))
+	case "del", "delete":
+		op, err = s.parseDelete(args)
+	case "nxdomain", "yxdomain", "nxrrset", "yxrrset":
+		op, err = s.parsePrereq(cmd, args)
+	case "server":
+		if len(args) < 1 || len(args) > 2 {
+			return false, fmt.Errorf("usage: server <name> [port]")
+		}
+		port := "53"
+		if len(args) == 2 {
+			port = args[1]
+		}
+		overrideSetting("server", net.JoinHostPort(args[0], port))
+		return false, nil
+	case "zone":
+		if len(args) != 1 {
+			return false, fmt.Errorf("usage: zone <name>")
+		}
+		s.zone = dns.Fqdn(args[0])
+		return false, nil
+	case "key":
+		if len(args) != 2 {
+			return false, fmt.Errorf("usage: key [alg:]name secret")
+		}
+		name := args[0]
+		if idx := strings.Index(name, ":"); idx >= 0 {
+			if _, ok := lookupTsigAlgorithm(name[:idx]); !ok {
+				return false, fmt.Errorf("unknown algorithm: %s", name[:idx])
+			}
+			overrideSetting("tsig-algorithm", name[:idx])
+			name = name[idx+1:]
+		}
+		overrideSetting("tsig-name", dns.Fqdn(name))
+		overrideSetting("tsig-secret", args[1])
+		return false, nil
+	case "ttl":
+		if len(args) != 1 {
+			return false, fmt.Errorf("usage: ttl <seconds>")
+		}
+		if _, err := strconv.ParseUint(args[0], 10, 32); err != nil {
+			return false, fmt.Errorf("invalid ttl: %s", args[0])
+		}
+		s.ttl = args[0]
+		return false, nil
+	case "class":
+		if len(args) != 1 {
+			return false, fmt.Errorf("usage: class <class>")
+		}
+		_, err := stripClass(args)
+		return false, err
+	case "send":
+		return false, s.send()
+	case "show":
+		if len(s.ops) > 0 {
+			fmt.Fprintln(s.out, buildUpdate(s.currentZone(), s.ops))
+		}
+		return false, nil
+	case "answer":
+		if s.answer != nil {
+			fmt.Fprintln(s.out, s.answer)
+		}
+		return false, nil
+	case "quit":
+		return true, nil
+	case "debug", "local", "realm", "gsstsig", "oldgsstsig", "check-names":
+		log.Printf("Ignoring unsupported command: %s", cmd)
+		return false, nil
+	default:
+		return false, fmt.Errorf("unknown command: %s", cmd)
+	}
+
+	if err != nil {
+		return false, err
+	}
+	s.ops = append(s.ops, op)
+	return false, nil
+}
+
+func nsupdate(args []string) int {
+	if len(args) > 1 {
+		log.Print("Usage: update-dns nsupdate [file]")
+		return 1
+	}
+
+	var r io.Reader = os.Stdin
+	if len(args) == 1 && args[0] != "-" {
+		f, err := os.Open(args[0])
+		if err != nil {
+			log.Printf("Unable to open input: %s", err)
+			return 1
+		}
+		defer f.Close()
+		r = f
+	}
+
+	s := &nsupdateSession{out: os.Stdout}
+	scanner := bufio.NewScanner(r)
+	lineno := 0
+	for scanner.Scan() {
+		lineno++
+		quit, err := s.execute(scanner.Text())
+		if err != nil {
+			log.Printf("line %d: %s", lineno, err)
+			return exitCode(err)
+		}
+		if quit {
+			return 0
+		}
+	}
+	if err := scanner.Err(); err != nil {
+		log.Printf("Unable to read input: %s", err)
+		return 1
+	}
+
+	// nsupdate sends any pending update at end of input
+	if err := s.send(); err != nil {
+		log.Print(err)
+		return exitCode(err)
+	}
+	return 0
+}
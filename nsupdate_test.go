@@ -0,0 +1,106 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"bytes"
+	"testing"
+)
+
+func TestNsupdateExecute(t *testing.T) {
+	testConfig(t, nil)
+	var out bytes.Buffer
+	s := &nsupdateSession{out: &out, interactive: true}
+
+	lines := []string{
+		"; a comment",
+		"zone example.org",
+		"ttl 600",
+		"",
+		"update add www.example.org. A 192.0.2.1",
+		"update delete old.example.org.",
+		"update delete www.example.org. 300 IN A 192.0.2.9",
+		"del mail.example.org. MX",
+		"prereq nxdomain new.example.org.",
+		"prereq yxrrset www.example.org. A 192.0.2.1",
+		"prereq nxrrset www.example.org. IN AAAA",
+	}
+	for _, line := range lines {
+		if quit, err := s.execute(line); quit || err != nil {
+			t.Fatalf("execute(%q) = %t, %v", line, quit, err)
+		}
+	}
+	if s.zone != "example.org." || s.ttl != "600" {
+		t.Errorf("got zone %q and ttl %q", s.zone, s.ttl)
+	}
+
+	want := []struct {
+		action string
+		key    string
+		ttl    uint32
+	}{
+		{"append", "www.example.org./A", 600},
+		{"delete", "old.example.org./ANY", 0},
+		{"remove", "www.example.org./A", 0},
+		{"delete", "mail.example.org./MX", 0},
+		{"nxdomain", "new.example.org./ANY", 0},
+		{"yxrr", "www.example.org./A", 0},
+		{"nxrrset", "www.example.org./AAAA", 0},
+	}
+	if len(s.ops) != len(want) {
+		t.Fatalf("got operations %q", formatOps(s.ops))
+	}
+	for i, op := range s.ops {
+		w := want[i]
+		if op.action != w.action || rrsetKey(op.rr) != w.key || (w.ttl != 0 && op.rr.Header().Ttl != w.ttl) {
+			t.Errorf("operation %d: got %s, want %s %s", i, formatOps(s.ops[i:i+1]), w.action, w.key)
+		}
+	}
+
+	if quit, err := s.execute("quit"); !quit || err != nil {
+		t.Errorf("quit didn't end the session: %v", err)
+	}
+	if out.Len() != 0 {
+		t.Errorf("unexpected output: %s", out.String())
+	}
+}
+
+func TestNsupdateExecuteErrors(t *testing.T) {
+	testConfig(t, nil)
+	tests := []string{
+		"frobnicate",
+		"zone",
+		"ttl soon",
+		"class CH",
+		"server",
+		"key ddns-key",
+		"key hmac-foo:ddns-key c2VjcmV0",
+		"update delete",
+		"update add www.example.org. A 192.0.2.256",
+		"prereq yxdomain www.example.org. A",
+		"prereq nxrrset www.example.org.",
+		"prereq yxrrset www.example.org. CH A",
+	}
+	for _, line := range tests {
+		s := &nsupdateSession{interactive: true}
+		if _, err := s.execute(line); err == nil {
+			t.Errorf("execute(%q) succeeded, want an error", line)
+		}
+		if len(s.ops) != 0 {
+			t.Errorf("execute(%q) added %q", line, formatOps(s.ops))
+		}
+	}
+}
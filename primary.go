@@ -0,0 +1,136 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"fmt"
+	"log"
+	"net"
+	"strings"
+
+	"github.com/miekg/dns"
+	"github.com/spf13/viper"
+)
+
+// The primary servers found with --find-primary, keyed by the lowercased
+// zone name. Updates for names in these zones are sent there instead of to
+// the configured server.
+var primaries = make(map[string]string)
+
+// primaryFor returns the primary server found for the most specific zone
+// containing name, if any.
+func primaryFor(name string) string {
+	name = strings.ToLower(dns.Fqdn(name))
+	for off, end := 0, false; !end; off, end = dns.NextLabel(name, off) {
+		if server, ok := primaries[name[off:]]; ok {
+			return server
+		}
+	}
+	return ""
+}
+
+// resolveHost returns the address of host, looked up on the configured
+// server (which is usually a resolver when --find-primary is needed), or
+// with the system resolver if that gives no answer.
+func resolveHost(host, server string) (string, error) {
+	c := new(dns.Client)
+	for _, rrtype := range []uint16{dns.TypeA, dns.TypeAAAA} {
+		m := new(dns.Msg)
+		m.SetQuestion(dns.Fqdn(host), rrtype)
+		r, err := clientExchange(c, m, server)
+		if err != nil {
+			continue
+		}
+		for _, rr := range r.Answer {
+			if ip := recordAddress(rr); ip != nil {
+				return ip.String(), nil
+			}
+		}
+	}
+
+	addrs, err := net.LookupHost(strings.TrimSuffix(host, "."))
+	if err != nil {
+		return "", err
+	}
+	return addrs[0], nil
+}
+
+// usePrimary makes the updates of zone go to the server named in the MNAME
+// field of its SOA record.
+func usePrimary(zone, mname, server string) {
+	addr, err := resolveHost(mname, server)
+	if err != nil {
+		log.Panicf("Unable to resolve %s, the primary server of %s: %s", mname, zone, err)
+	}
+	primaries[zone] = net.JoinHostPort(addr, "53")
+	infof("%s is not authoritative for %s, using its primary %s (%s)",
+		server, zone, mname, primaries[zone])
+}
+
+// delegatedPrimary returns the zone and MNAME of the zone delegated in the
+// referral r, asking the nameservers it names for the SOA record.
+func delegatedPrimary(r *dns.Msg, server string) (string, string, error) {
+	c := new(dns.Client)
+	for _, k := range r.Ns {
+		ns, ok := k.(*dns.NS)
+		if !ok {
+			continue
+		}
+		addr, err := resolveHost(ns.Ns, server)
+		if err != nil {
+			continue
+		}
+
+		m := new(dns.Msg)
+		m.SetQuestion(ns.Hdr.Name, dns.TypeSOA)
+		a, err := clientExchange(c, m, net.JoinHostPort(addr, "53"))
+		if err != nil {
+			continue
+		}
+		for _, rr := range a.Answer {
+			if soa, ok := rr.(*dns.SOA); ok {
+				return strings.ToLower(soa.Hdr.Name), soa.Ns, nil
+			}
+		}
+	}
+	return "", "", fmt.Errorf("no SOA record found on the nameservers")
+}
+
+// findPrimary queries the configured server for the SOA record of zone,
+// and uses the primary server it names if the answer isn't authoritative.
+// It is used for zones given with --zone, which aren't discovered.
+func findPrimary(zone string) {
+	if !viper.GetBool("find-primary") || len(primaryFor(zone)) > 0 {
+		return
+	}
+
+	server := setting(zone, "server")
+	m := new(dns.Msg)
+	m.SetQuestion(dns.Fqdn(zone), dns.TypeSOA)
+	r, err := clientExchange(new(dns.Client), m, server)
+	if err != nil {
+		log.Panicf("Unable to look up the primary of %s: %s", zone, err)
+	}
+	if r.Authoritative {
+		return
+	}
+	for _, rr := range r.Answer {
+		if soa, ok := rr.(*dns.SOA); ok {
+			usePrimary(strings.ToLower(dns.Fqdn(zone)), soa.Ns, server)
+			return
+		}
+	}
+}
@@ -0,0 +1,127 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"fmt"
+	"log"
+	"os"
+	"time"
+
+	"github.com/spf13/viper"
+	"golang.org/x/term"
+)
+
+// How long an operation has to run before a progress line is drawn on a
+// terminal, how often the line is redrawn, and how often progress is
+// logged when stderr isn't a terminal.
+const (
+	progressDelay    = time.Second
+	progressRedraw   = 200 * time.Millisecond
+	progressInterval = 10 * time.Second
+)
+
+// progress reports how far a long-running operation has got: as a status
+// line with counts and an ETA that is redrawn on stderr if it is a
+// terminal, and as a log line every progressInterval otherwise. total is
+// zero if it isn't known in advance (as for zone transfers).
+type progress struct {
+	label string
+	unit  string
+	total int
+	done  int
+	start time.Time
+	last  time.Time
+	tty   bool
+	drawn bool
+}
+
+func newProgress(label, unit string, total int) *progress {
+	now := time.Now()
+	return &progress{
+		label: label,
+		unit:  unit,
+		total: total,
+		start: now,
+		last:  now,
+		tty:   log.Writer() == os.Stderr && term.IsTerminal(int(os.Stderr.Fd())) &&
+			!(viper.GetBool("dry-run") && term.IsTerminal(int(os.Stdout.Fd()))),
+	}
+}
+
+// Write clears the status line before anything is logged and redraws it
+// afterwards, so the status line stays below the log messages.
+func (p *progress) Write(b []byte) (int, error) {
+	fmt.Fprint(os.Stderr, "\r\033[K")
+	n, err := os.Stderr.Write(b)
+	fmt.Fprint(os.Stderr, p.status())
+	return n, err
+}
+
+func (p *progress) enabled() bool {
+	return viper.GetBool("progress") && logLevel >= levelInfo
+}
+
+func (p *progress) status() string {
+	s := fmt.Sprintf("%s: %d", p.label, p.done)
+	if p.total > 0 {
+		s += fmt.Sprintf("/%d %s (%d%%)", p.total, p.unit, p.done*100/p.total)
+		if p.done > 0 && p.done < p.total {
+			elapsed := time.Since(p.start)
+			eta := elapsed * time.Duration(p.total-p.done) / time.Duration(p.done)
+			s += fmt.Sprintf(", ETA %s", eta.Round(time.Second))
+		}
+	} else {
+		s += " " + p.unit
+	}
+	return s
+}
+
+// add counts n more units as done and updates the progress output if it is
+// due.
+func (p *progress) add(n int) {
+	p.done += n
+	if !p.enabled() {
+		return
+	}
+
+	now := time.Now()
+	switch {
+	case p.tty:
+		if now.Sub(p.start) < progressDelay || (p.drawn && now.Sub(p.last) < progressRedraw) {
+			return
+		}
+		fmt.Fprintf(os.Stderr, "\r\033[K%s", p.status())
+		if !p.drawn {
+			log.SetOutput(p)
+			p.drawn = true
+		}
+	case now.Sub(p.last) >= progressInterval:
+		infof("%s", p.status())
+	default:
+		return
+	}
+	p.last = now
+}
+
+// finish clears the status line.
+func (p *progress) finish() {
+	if p.drawn {
+		fmt.Fprint(os.Stderr, "\r\033[K")
+		log.SetOutput(os.Stderr)
+		p.drawn = false
+	}
+}
@@ -0,0 +1,254 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"fmt"
+	"log"
+	"net"
+	"strings"
+	"time"
+
+	"github.com/miekg/dns"
+	"github.com/spf13/viper"
+)
+
+// nameservers returns the addresses of the authoritative servers of zone,
+// according to its NS records on the configured server.
+func nameservers(zone string) ([]string, error) {
+	rrs, err := lookup(zone, dns.TypeNS)
+	if err != nil {
+		return nil, err
+	}
+
+	var servers []string
+	for _, rr := range rrs {
+		ns, ok := rr.(*dns.NS)
+		if !ok {
+			continue
+		}
+		addrs, err := net.LookupHost(strings.TrimSuffix(ns.Ns, "."))
+		if err != nil {
+			log.Printf("Unable to resolve nameserver %s: %s", ns.Ns, err)
+			continue
+		}
+		for _, addr := range addrs {
+			servers = append(servers, net.JoinHostPort(addr, "53"))
+		}
+	}
+
+	if len(servers) == 0 {
+		return nil, fmt.Errorf("no nameservers found for %s", zone)
+	}
+	return servers, nil
+}
+
+// queryServer sends a query to server and returns the answers with the
+// given name and type. Queries to the configured server are signed, since
+// it may not answer unsigned ones; others are not.
+func queryServer(server, name string, rrtype uint16) ([]dns.RR, error) {
+	c := new(dns.Client)
+	m := new(dns.Msg)
+	m.SetQuestion(dns.Fqdn(name), rrtype)
+
+	var r *dns.Msg
+	var err error
+	if server == setting(name, "server") {
+		r, err = exchange(m)
+	} else {
+		r, err = clientExchange(c, m, server)
+		if err == nil && r.Truncated {
+			c.Net = "tcp"
+			r, err = clientExchange(c, m, server)
+		}
+	}
+	if err != nil {
+		return nil, err
+	} else if r.Rcode != dns.RcodeSuccess && r.Rcode != dns.RcodeNameError {
+		return nil, fmt.Errorf("Server returned error code %s", dns.RcodeToString[r.Rcode])
+	}
+
+	var rrs []dns.RR
+	for _, rr := range r.Answer {
+		if rr.Header().Rrtype == rrtype && strings.EqualFold(rr.Header().Name, dns.Fqdn(name)) {
+			rrs = append(rrs, rr)
+		}
+	}
+	return rrs, nil
+}
+
+// waitFor polls each of servers until check accepts the records they serve
+// for name, or the timeout expires.
+func waitFor(servers []string, name string, rrtype uint16,
+	check func([]dns.RR) bool, timeout time.Duration) error {
+
+	deadline := time.Now().Add(timeout)
+	pending := servers
+	for {
+		var waiting []string
+		for _, server := range pending {
+			rrs, err := queryServer(server, name, rrtype)
+			if err == nil && check(rrs) {
+				infof("%s is serving the expected data", server)
+				continue
+			}
+			waiting = append(waiting, server)
+		}
+
+		pending = waiting
+		if len(pending) == 0 {
+			return nil
+		} else if time.Now().After(deadline) {
+			return fmt.Errorf("timed out waiting for %s", strings.Join(pending, ", "))
+		}
+		if err := sleep(5 * time.Second); err != nil {
+			return err
+		}
+	}
+}
+
+// serialAtLeast compares SOA serial numbers using serial number arithmetic
+// (RFC 1982), so it also works when the serial wraps around.
+func serialAtLeast(serial, min uint32) bool {
+	return int32(serial-min) >= 0
+}
+
+// waitSecondaries waits, if --wait-secondaries is given, until all the
+// authoritative servers of zone serve at least the serial the configured
+// server has now.
+func waitSecondaries(zone string) error {
+	if len(viper.GetString("wait-secondaries")) == 0 || viper.GetBool("dry-run") {
+		return nil
+	}
+	timeout, err := time.ParseDuration(viper.GetString("wait-secondaries"))
+	if err != nil {
+		return fmt.Errorf("invalid --wait-secondaries timeout: %s", err)
+	}
+
+	rrs, err := lookup(zone, dns.TypeSOA)
+	if err != nil {
+		return err
+	}
+	var serial uint32
+	var found bool
+	for _, rr := range rrs {
+		if soa, ok := rr.(*dns.SOA); ok {
+			serial, found = soa.Serial, true
+		}
+	}
+	if !found {
+		return fmt.Errorf("no SOA record found for %s", zone)
+	}
+
+	servers, err := nameservers(zone)
+	if err != nil {
+		return err
+	}
+
+	infof("Waiting for %d servers to serve serial %d of %s", len(servers), serial, zone)
+	check := func(rrs []dns.RR) bool {
+		for _, rr := range rrs {
+			if soa, ok := rr.(*dns.SOA); ok && serialAtLeast(soa.Serial, serial) {
+				return true
+			}
+		}
+		return false
+	}
+	return waitFor(servers, zone, dns.TypeSOA, check, timeout)
+}
+
+// expectedRecord parses the '<name> <type> <value>' arguments of verify and
+// wait into the record that should be served.
+func expectedRecord(args []string) (dns.RR, error) {
+	if len(args) < 3 {
+		return nil, fmt.Errorf("expected <name> <type> <value>")
+	}
+	if _, err := parseType(args[1]); err != nil {
+		return nil, err
+	}
+	return parseRecord(strings.Join(args, " "), "replace")
+}
+
+func hasRecord(rrs []dns.RR, expected dns.RR) bool {
+	return findRecord(rrs, expected) != nil
+}
+
+// resolverAddrs adds the default port to the configured resolvers.
+func resolverAddrs() []string {
+	var addrs []string
+	for _, r := range viper.GetStringSlice("verify.resolvers") {
+		if _, _, err := net.SplitHostPort(r); err != nil {
+			r = net.JoinHostPort(r, "53")
+		}
+		addrs = append(addrs, r)
+	}
+	return addrs
+}
+
+func verify(args []string) int {
+	expected, err := expectedRecord(args)
+	if err != nil {
+		log.Printf("Usage: update-dns verify [--resolver <addr>] <name> <type> <value>: %s", err)
+		return 1
+	}
+	name := expected.Header().Name
+
+	servers, err := nameservers(getZone(name))
+	if err != nil {
+		log.Printf("Unable to find nameservers: %s", err)
+		return 1
+	}
+	servers = append(servers, resolverAddrs()...)
+
+	check := func(rrs []dns.RR) bool { return hasRecord(rrs, expected) }
+	err = waitFor(servers, name, expected.Header().Rrtype, check, viper.GetDuration("verify.timeout"))
+	if err != nil {
+		log.Print(err)
+		return 1
+	}
+
+	infof("All %d servers are serving the record", len(servers))
+	return 0
+}
+
+func wait(args []string) int {
+	expected, err := expectedRecord(args)
+	if err != nil {
+		log.Printf("Usage: update-dns wait [--timeout <secs>] [--all-servers] <name> <type> <value>: %s", err)
+		return 1
+	}
+	name := expected.Header().Name
+
+	servers := []string{serverFor(name)}
+	if viper.GetBool("all-servers") {
+		servers, err = nameservers(getZone(name))
+		if err != nil {
+			log.Printf("Unable to find nameservers: %s", err)
+			return 1
+		}
+	}
+
+	check := func(rrs []dns.RR) bool { return hasRecord(rrs, expected) }
+	timeout := time.Duration(viper.GetInt("timeout")) * time.Second
+	if timeout <= 0 {
+		timeout = 300 * time.Second
+	}
+	if err := waitFor(servers, name, expected.Header().Rrtype, check, timeout); err != nil {
+		log.Print(err)
+		return 1
+	}
+	return 0
+}
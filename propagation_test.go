@@ -0,0 +1,42 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"testing"
+)
+
+func TestSerialAtLeast(t *testing.T) {
+	tests := []struct {
+		serial, min uint32
+		want        bool
+	}{
+		{1, 1, true},
+		{2, 1, true},
+		{1, 2, false},
+		{2026101400, 2026101399, true},
+		// The serial wraps around (RFC 1982)
+		{0, 0xffffffff, true},
+		{5, 0xfffffff0, true},
+		{0xffffffff, 0, false},
+		{0x7fffffff, 0, true},
+	}
+	for _, test := range tests {
+		if got := serialAtLeast(test.serial, test.min); got != test.want {
+			t.Errorf("serialAtLeast(%d, %d) = %t, want %t", test.serial, test.min, got, test.want)
+		}
+	}
+}
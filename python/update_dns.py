@@ -0,0 +1,190 @@
+# Copyright (c) 2026, the update-dns contributors
+#
+# This program is free software: you can redistribute it and/or modify
+# it under the terms of the GNU General Public License as published by
+# the Free Software Foundation, either version 3 of the License, or
+# (at your option) any later version.
+#
+# This program is distributed in the hope that it will be useful,
+# but WITHOUT ANY WARRANTY; without even the implied warranty of
+# MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+# GNU General Public License for more details.
+#
+# You should have received a copy of the GNU General Public License
+# along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+"""Python bindings for update-dns, using the C API in libupdatedns.so.
+
+Build the library with
+
+    go build -buildmode=c-shared -o libupdatedns.so ./capi
+
+and put it where the dynamic linker finds it, next to this module, or
+in the file named by the UPDATE_DNS_LIBRARY environment variable.
+
+    import update_dns
+
+    u = update_dns.Updater()   # reads ~/.update-dns/update-dns.yaml
+    u.replace("test.example.org 300 A 127.0.0.1")
+    for record in u.query("test.example.org", "A"):
+        print(record["data"])
+"""
+
+import ctypes
+import json
+import os
+import threading
+
+
+class Error(Exception):
+    """An update-dns error, with its kind and the exit status the command
+    line tool would exit with."""
+
+    def __init__(self, message, kind, exit_code):
+        super().__init__(message)
+        self.kind = kind
+        self.exit_code = exit_code
+
+
+class ConfigError(Error):
+    """Invalid or missing settings."""
+
+
+class ResolutionError(Error):
+    """The zone of a name couldn't be found."""
+
+
+class TransportError(Error):
+    """No answer from the server."""
+
+
+class TSIGError(Error):
+    """The server rejected the TSIG signature."""
+
+
+class RcodeError(Error):
+    """The server answered with an error."""
+
+
+class ValidationError(Error):
+    """An invalid record, or a refused risky change."""
+
+
+class PartialError(Error):
+    """Only some of the updates of a change were made."""
+
+
+class CancelledError(Error):
+    """The updates were cancelled."""
+
+
+# Indexed by updatedns.ErrorKind
+_KINDS = [
+    ("other", Error),
+    ("config", ConfigError),
+    ("resolution", ResolutionError),
+    ("transport", TransportError),
+    ("tsig", TSIGError),
+    ("rcode", RcodeError),
+    ("validation", ValidationError),
+    ("partial", PartialError),
+    ("cancelled", CancelledError),
+]
+
+
+def _load():
+    names = [os.environ.get("UPDATE_DNS_LIBRARY"),
+             os.path.join(os.path.dirname(os.path.abspath(__file__)), "libupdatedns.so"),
+             "libupdatedns.so"]
+    for name in names:
+        if not name:
+            continue
+        try:
+            lib = ctypes.CDLL(name)
+            break
+        except OSError:
+            continue
+    else:
+        raise ImportError("libupdatedns.so not found (set UPDATE_DNS_LIBRARY)")
+
+    for func in ("init_json", "replace", "append", "delete", "last_error_kind", "last_status"):
+        getattr(lib, "update_dns_" + func).restype = ctypes.c_int
+    lib.update_dns_init_json.argtypes = [ctypes.c_char_p, ctypes.c_char_p]
+    lib.update_dns_replace.argtypes = [ctypes.c_char_p]
+    lib.update_dns_append.argtypes = [ctypes.c_char_p]
+    lib.update_dns_delete.argtypes = [ctypes.c_char_p, ctypes.c_char_p]
+    # Returned as void pointers, so they can be freed
+    lib.update_dns_query.restype = ctypes.c_void_p
+    lib.update_dns_query.argtypes = [ctypes.c_char_p, ctypes.c_char_p]
+    lib.update_dns_last_error.restype = ctypes.c_void_p
+    lib.update_dns_free.argtypes = [ctypes.c_void_p]
+    return lib
+
+
+_lib = _load()
+
+# Held from each call until its error has been read, as the library only
+# keeps the last error of the whole process
+_lock = threading.Lock()
+
+
+def _string(ptr):
+    if not ptr:
+        return None
+    try:
+        return ctypes.string_at(ptr).decode()
+    finally:
+        _lib.update_dns_free(ptr)
+
+
+def _encode(s):
+    return s.encode() if s is not None else None
+
+
+def _check(status):
+    if status == 0:
+        return
+    message = _string(_lib.update_dns_last_error()) or "update-dns error"
+    kind = _lib.update_dns_last_error_kind()
+    name, cls = _KINDS[kind] if 0 <= kind < len(_KINDS) else _KINDS[0]
+    raise cls(message, name, status)
+
+
+def _call(func, *args):
+    with _lock:
+        _check(func(*args))
+
+
+class Updater:
+    """Sends updates with the settings in the config file (the default
+    ones if config_file is None), with the keyword arguments (the same
+    keys as in the config file, with _ for -) taking precedence.
+
+    The settings are global in the library, so only one Updater should
+    be used at a time."""
+
+    def __init__(self, config_file=None, **settings):
+        settings = {k.replace("_", "-"): v for k, v in settings.items()}
+        _call(_lib.update_dns_init_json, _encode(config_file),
+              _encode(json.dumps(settings)))
+
+    def replace(self, record):
+        """Replace the RRset of record, e.g. "test.example.org 300 A 127.0.0.1"."""
+        _call(_lib.update_dns_replace, _encode(record))
+
+    def append(self, record):
+        """Add record to its RRset."""
+        _call(_lib.update_dns_append, _encode(record))
+
+    def delete(self, name, rrtype=None):
+        """Delete the RRset of type rrtype at name, or all its records."""
+        _call(_lib.update_dns_delete, _encode(name), _encode(rrtype))
+
+    def query(self, name, rrtype=None):
+        """Return the records at name (of type rrtype, or all types) as
+        dicts with the name, ttl, class, type and data of each."""
+        with _lock:
+            data = _string(_lib.update_dns_query(_encode(name), _encode(rrtype)))
+            if data is None:
+                _check(_lib.update_dns_last_status())
+        return json.loads(data)
@@ -0,0 +1,209 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"encoding/json"
+	"fmt"
+	"io"
+	"log"
+	"os"
+	"strings"
+
+	"github.com/miekg/dns"
+	"github.com/spf13/viper"
+)
+
+type jsonRecord struct {
+	Name  string `json:"name"`
+	TTL   uint32 `json:"ttl"`
+	Class string `json:"class"`
+	Type  string `json:"type"`
+	Data  string `json:"data"`
+}
+
+func toJSONRecord(rr dns.RR) jsonRecord {
+	hdr := rr.Header()
+	return jsonRecord{
+		Name:  toUnicode(hdr.Name),
+		TTL:   hdr.Ttl,
+		Class: dns.ClassToString[hdr.Class],
+		Type:  dns.TypeToString[hdr.Rrtype],
+		Data:  toUnicode(strings.TrimPrefix(rr.String(), hdr.String())),
+	}
+}
+
+// printRecords writes records to w in the configured output format.
+func printRecords(w io.Writer, rrs []dns.RR) {
+	if viper.GetString("output") == "json" {
+		records := make([]jsonRecord, 0, len(rrs))
+		for _, rr := range rrs {
+			records = append(records, toJSONRecord(rr))
+		}
+		enc := json.NewEncoder(w)
+		enc.SetIndent("", "  ")
+		enc.Encode(records)
+		return
+	}
+
+	for _, line := range formatRecords(w, rrs) {
+		fmt.Fprintln(w, line)
+	}
+}
+
+func parseType(s string) (uint16, error) {
+	t, ok := dns.StringToType[strings.ToUpper(s)]
+	if !ok {
+		return 0, fmt.Errorf("Unknown record type: %s", s)
+	}
+	return t, nil
+}
+
+// lookup queries the configured server for name. A non-existent name is
+// not an error, but simply returns no records. ANY queries are done with
+// lookupAny.
+func lookup(name string, qtype uint16) ([]dns.RR, error) {
+	if qtype == dns.TypeANY {
+		return lookupAny(name)
+	}
+
+	m := new(dns.Msg)
+	m.SetQuestion(dns.Fqdn(name), qtype)
+
+	r, err := exchange(m)
+	if err != nil {
+		return nil, err
+	} else if r.Rcode == dns.RcodeNameError {
+		return nil, nil
+	} else if r.Rcode != dns.RcodeSuccess {
+		return nil, fmt.Errorf("Server returned error code %s",
+			dns.RcodeToString[r.Rcode])
+	}
+	return r.Answer, nil
+}
+
+// The types queried for one by one when the server won't answer ANY
+// queries and refuses zone transfers.
+var anyFallbackTypes = []uint16{
+	dns.TypeSOA, dns.TypeNS, dns.TypeCNAME, dns.TypeDNAME, dns.TypeA,
+	dns.TypeAAAA, dns.TypeMX, dns.TypeTXT, dns.TypeSPF, dns.TypeSRV,
+	dns.TypePTR, dns.TypeNAPTR, dns.TypeCAA, dns.TypeSSHFP, dns.TypeTLSA,
+	dns.TypeDS, dns.TypeDNSKEY, dns.TypeHINFO, dns.TypeLOC, dns.TypeURI,
+}
+
+// minimalAny reports whether r is the answer of a server that doesn't
+// answer ANY queries: a single synthesized HINFO record (RFC 8482) or a
+// NOTIMP or REFUSED error. BIND's minimal-any answers can't be told from
+// a full answer, but are only given over UDP, so ANY queries are sent over
+// TCP (see exchangeOnce).
+func minimalAny(r *dns.Msg) bool {
+	if r.Rcode == dns.RcodeNotImplemented || r.Rcode == dns.RcodeRefused {
+		return true
+	}
+	if len(r.Answer) != 1 {
+		return false
+	}
+	hinfo, ok := r.Answer[0].(*dns.HINFO)
+	return ok && strings.EqualFold(hinfo.Cpu, "RFC8482")
+}
+
+// lookupAny returns all the records at name. If the server doesn't answer
+// ANY queries, the records are taken from a transfer of the zone, or, if
+// that is refused too, looked up one type at a time.
+func lookupAny(name string) ([]dns.RR, error) {
+	name = dns.Fqdn(name)
+	m := new(dns.Msg)
+	m.SetQuestion(name, dns.TypeANY)
+
+	r, err := exchange(m)
+	if err != nil {
+		return nil, err
+	}
+	if !minimalAny(r) {
+		if r.Rcode == dns.RcodeNameError {
+			return nil, nil
+		} else if r.Rcode != dns.RcodeSuccess {
+			return nil, fmt.Errorf("Server returned error code %s",
+				dns.RcodeToString[r.Rcode])
+		}
+		return r.Answer, nil
+	}
+
+	var res []dns.RR
+	if rrs, err := transfer(getZone(name)); err == nil {
+		for _, rr := range rrs {
+			if strings.EqualFold(rr.Header().Name, name) {
+				res = append(res, rr)
+			}
+		}
+		return res, nil
+	}
+
+	return recordsOfTypes(name, anyFallbackTypes)
+}
+
+// recordsOfTypes returns the records of types at name, querying for one
+// type at a time. Other records in the answers, such as those a CNAME
+// points at, are left out.
+func recordsOfTypes(name string, types []uint16) ([]dns.RR, error) {
+	var res []dns.RR
+	for _, rrtype := range types {
+		rrs, err := lookup(name, rrtype)
+		if err != nil {
+			return nil, err
+		}
+		for _, rr := range rrs {
+			if rr.Header().Rrtype == rrtype && strings.EqualFold(rr.Header().Name, name) {
+				res = append(res, rr)
+			}
+		}
+	}
+	return res, nil
+}
+
+// parseQueryArgs parses the '<name> [type]' arguments of the query command.
+func parseQueryArgs(args []string) (string, uint16, error) {
+	if len(args) < 1 || len(args) > 2 {
+		return "", 0, fmt.Errorf("Usage: query <name> [type]")
+	}
+
+	qtype := dns.TypeANY
+	if len(args) > 1 {
+		t, err := parseType(args[1])
+		if err != nil {
+			return "", 0, err
+		}
+		qtype = t
+	}
+	return expandName(args[0]), qtype, nil
+}
+
+func query(args []string) int {
+	name, qtype, err := parseQueryArgs(args)
+	if err != nil {
+		log.Print(err)
+		return 1
+	}
+
+	rrs, err := lookup(name, qtype)
+	if err != nil {
+		log.Printf("Query failed: %s", err)
+		return 1
+	}
+
+	printRecords(os.Stdout, rrs)
+	return 0
+}
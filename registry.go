@@ -0,0 +1,138 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"fmt"
+	"strconv"
+	"strings"
+	"time"
+
+	"github.com/miekg/dns"
+	"github.com/spf13/viper"
+)
+
+// With registry.enabled, each update adding records at a name also sets an
+// ownership record there: a TXT record at registry.prefix + name saying
+// when update-dns last updated the name, so prune --older-than can find the
+// names that haven't been updated for a long time.
+
+// The start of the value of ownership records, followed by the time.
+const ownershipValue = "update-dns updated="
+
+func registryEnabled() bool {
+	return viper.GetBool("registry.enabled")
+}
+
+func ownershipName(name string) string {
+	return strings.ToLower(viper.GetString("registry.prefix") + name)
+}
+
+// isOwnershipName reports whether name is that of an ownership record.
+func isOwnershipName(name string) bool {
+	return registryEnabled() && strings.HasPrefix(strings.ToLower(name), strings.ToLower(viper.GetString("registry.prefix")))
+}
+
+func ownershipRecord(name string, ttl uint32) *dns.TXT {
+	return &dns.TXT{Hdr: dns.RR_Header{Name: ownershipName(name),
+		Rrtype: dns.TypeTXT,
+		Class:  dns.ClassINET,
+		Ttl:    ttl},
+		Txt: []string{ownershipValue + time.Now().UTC().Format(time.RFC3339)}}
+}
+
+// ownershipTime returns the time in an ownership record, and whether rr is
+// one.
+func ownershipTime(rr dns.RR) (time.Time, bool) {
+	txt, ok := rr.(*dns.TXT)
+	if !ok || len(txt.Txt) != 1 || !strings.HasPrefix(txt.Txt[0], ownershipValue) {
+		return time.Time{}, false
+	}
+	t, err := time.Parse(time.RFC3339, strings.TrimPrefix(txt.Txt[0], ownershipValue))
+	return t, err == nil
+}
+
+// addOwnership returns ops with the ownership records of the names they
+// add records at set to the current time, and those of the names they
+// delete altogether deleted.
+func addOwnership(ops []operation) []operation {
+	res := append([]operation{}, ops...)
+	seen := make(map[string]bool)
+	for _, op := range ops {
+		name := strings.ToLower(op.rr.Header().Name)
+		if seen[name] || isOwnershipName(name) || strings.HasPrefix(name, "*.") {
+			continue
+		}
+		switch {
+		case op.action == "replace" || op.action == "append":
+			res = append(res, operation{"replace", ownershipRecord(name, op.rr.Header().Ttl)})
+		case op.action == "delete" && op.rr.Header().Rrtype == dns.TypeANY:
+			res = append(res, operation{"delete", &dns.ANY{Hdr: dns.RR_Header{Name: ownershipName(name),
+				Rrtype: dns.TypeANY}}})
+		default:
+			continue
+		}
+		seen[name] = true
+	}
+	return res
+}
+
+// keepOwnership puts the time of the ownership records in before back into
+// those set by ops when they are less than registry.refresh old, so an
+// update that changes nothing else can still be skipped.
+func keepOwnership(ops []operation, before []dns.RR) {
+	for i, op := range ops {
+		txt, ok := op.rr.(*dns.TXT)
+		if op.action != "replace" || !ok || !isOwnershipName(txt.Hdr.Name) {
+			continue
+		}
+		for _, rr := range before {
+			t, ok := ownershipTime(rr)
+			if ok && strings.EqualFold(rr.Header().Name, txt.Hdr.Name) &&
+				time.Since(t) < viper.GetDuration("registry.refresh") {
+				kept := dns.Copy(txt).(*dns.TXT)
+				kept.Txt = rr.(*dns.TXT).Txt
+				ops[i].rr = kept
+			}
+		}
+	}
+}
+
+// parseAge parses a duration, which can also be given in days (e.g. 30d).
+func parseAge(s string) (time.Duration, error) {
+	if days := strings.TrimSuffix(s, "d"); days != s {
+		n, err := strconv.ParseUint(days, 10, 32)
+		if err != nil {
+			return 0, fmt.Errorf("invalid age: %s", s)
+		}
+		return time.Duration(n) * 24 * time.Hour, nil
+	}
+	return time.ParseDuration(s)
+}
+
+// staleNames returns the names in rrs whose ownership records say they
+// were last updated more than age ago. Names without an ownership record
+// aren't included.
+func staleNames(rrs []dns.RR, age time.Duration) map[string]bool {
+	stale := make(map[string]bool)
+	prefix := strings.ToLower(viper.GetString("registry.prefix"))
+	for _, rr := range rrs {
+		if t, ok := ownershipTime(rr); ok && time.Since(t) > age {
+			stale[strings.TrimPrefix(strings.ToLower(rr.Header().Name), prefix)] = true
+		}
+	}
+	return stale
+}
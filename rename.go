@@ -0,0 +1,110 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"log"
+	"strings"
+
+	"github.com/miekg/dns"
+)
+
+// recordsAt returns the records at name that can be changed with dynamic
+// updates, i.e. everything except the DNSSEC records maintained by the
+// server.
+func recordsAt(name string) ([]dns.RR, error) {
+	rrs, err := lookup(name, dns.TypeANY)
+	if err != nil {
+		return nil, err
+	}
+
+	var res []dns.RR
+	for _, rr := range rrs {
+		if !strings.EqualFold(rr.Header().Name, dns.Fqdn(name)) {
+			continue
+		}
+		switch rr.Header().Rrtype {
+		case dns.TypeRRSIG, dns.TypeNSEC, dns.TypeNSEC3, dns.TypeNSEC3PARAM:
+			continue
+		}
+		res = append(res, rr)
+	}
+	return res, nil
+}
+
+// withName returns a copy of rr with the owner name changed to name.
+func withName(rr dns.RR, name string) dns.RR {
+	rr = dns.Copy(rr)
+	rr.Header().Name = dns.Fqdn(name)
+	return rr
+}
+
+func rename(args []string) int {
+	if len(args) != 2 {
+		log.Print("Usage: update-dns rename <old> <new>")
+		return 1
+	}
+	oldName, newName := expandName(args[0]), expandName(args[1])
+
+	rrs, err := recordsAt(oldName)
+	if err != nil {
+		log.Printf("Unable to look up %s: %s", oldName, err)
+		return 1
+	} else if len(rrs) == 0 {
+		log.Printf("No records found at %s", oldName)
+		return 1
+	}
+
+	oldZone, newZone := getZone(oldName), getZone(newName)
+	if strings.EqualFold(oldName, oldZone) {
+		log.Printf("Refusing to rename the zone apex %s", oldName)
+		return 1
+	}
+
+	var adds []operation
+	for _, rr := range rrs {
+		adds = append(adds, operation{"replace", withName(rr, newName)})
+	}
+	removeOld := []operation{
+		{"yxdomain", &dns.ANY{Hdr: dns.RR_Header{Name: oldName}}},
+		{"delete", &dns.ANY{Hdr: dns.RR_Header{Name: oldName, Rrtype: dns.TypeANY}}},
+	}
+
+	if oldZone == newZone {
+		// Both in one message, so the server applies them atomically
+		err = update(oldZone, append(removeOld, adds...))
+		if err != nil {
+			log.Printf("Unable to send update: %s", err)
+			return exitCode(err)
+		}
+	} else {
+		// Add the new name first, so there is no point at which neither
+		// name resolves
+		err = update(newZone, adds)
+		if err != nil {
+			log.Printf("Unable to create %s: %s", newName, err)
+			return exitCode(err)
+		}
+		err = update(oldZone, removeOld)
+		if err != nil {
+			log.Printf("Created %s, but unable to remove %s: %s", newName, oldName, err)
+			return exitPartial
+		}
+	}
+
+	infof("Renamed %s to %s (%d records)", oldName, newName, len(rrs))
+	return 0
+}
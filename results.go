@@ -0,0 +1,260 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"encoding/json"
+	"log"
+	"os"
+	"time"
+
+	"github.com/miekg/dns"
+	"github.com/spf13/viper"
+)
+
+type jsonOperation struct {
+	Action string `json:"action"`
+	Record string `json:"record"`
+}
+
+// An updateResult describes an update sent (or not) to a zone, for the
+// JSON output of update commands.
+type updateResult struct {
+	Zone       string          `json:"zone"`
+	Server     string          `json:"server"`
+	Operations []jsonOperation `json:"operations"`
+	Before     []string        `json:"before"`
+	After      []string        `json:"after"`
+	Status     string          `json:"status"` // sent, unchanged, dry-run or failed
+	Rcode      string          `json:"rcode,omitempty"`
+	Error      string          `json:"error,omitempty"`
+	Duration   float64         `json:"duration"` // in seconds
+
+	// The records removed and added, for the report
+	removed, added []dns.RR
+}
+
+// The results of the updates made so far.
+var updateResults []updateResult
+
+// jsonResults reports whether the results of updates are printed as JSON.
+func jsonResults() bool {
+	return viper.GetString("output") == "json"
+}
+
+// collectResults reports whether the results of updates are kept, for the
+// JSON output, the report or the metrics.
+func collectResults() bool {
+	return jsonResults() || viper.GetInt("report-fd") > 0 || len(viper.GetString("report-file")) > 0 ||
+		metricsEnabled()
+}
+
+// addResult records the result of the update of zone with ops, for the
+// JSON output and the journald or syslog log. The records before and after
+// the update are known if state is.
+func addResult(zone string, ops []operation, state *journalEntry, status string,
+	r *dns.Msg, err error, duration time.Duration) {
+	if !collectResults() && backend == nil {
+		return
+	}
+	res := updateResult{
+		Zone:       zone,
+		Server:     setting(zone, "server"),
+		Operations: []jsonOperation{},
+		Before:     []string{},
+		After:      []string{},
+		Status:     status,
+		Duration:   duration.Seconds(),
+	}
+	for _, op := range ops {
+		res.Operations = append(res.Operations, jsonOperation{op.action, op.rr.String()})
+	}
+	if state != nil {
+		res.Before = state.Before
+		for _, rr := range applyToRecords(state.before, ops) {
+			res.After = append(res.After, rr.String())
+		}
+	}
+	res.removed, res.added = updateChanges(state, ops)
+	if r != nil && status != "dry-run" && status != "unchanged" {
+		res.Rcode = dns.RcodeToString[r.Rcode]
+	}
+	if err != nil {
+		res.Error = err.Error()
+	}
+
+	var names, types []string
+	seen := make(map[string]bool)
+	for _, op := range ops {
+		name, rrtype := op.rr.Header().Name, dns.TypeToString[op.rr.Header().Rrtype]
+		if !seen["name "+name] {
+			seen["name "+name] = true
+			names = append(names, name)
+		}
+		if !seen["type "+rrtype] {
+			seen["type "+rrtype] = true
+			types = append(types, rrtype)
+		}
+	}
+	logResult(&res, names, types)
+
+	if collectResults() {
+		updateResults = append(updateResults, res)
+	}
+}
+
+// updateChanges returns the records removed and added by ops: exactly if
+// the records before the update (state) are known, and otherwise as given
+// in ops.
+func updateChanges(state *journalEntry, ops []operation) (removed, added []dns.RR) {
+	if state != nil {
+		return recordChanges(state.before, ops)
+	}
+	for _, op := range ops {
+		switch op.action {
+		case "replace", "append":
+			added = append(added, op.rr)
+		case "delete", "remove":
+			removed = append(removed, op.rr)
+		}
+	}
+	return removed, added
+}
+
+// recordChanges returns the records in before that ops remove and the
+// records they add. A changed TTL shows up as both.
+func recordChanges(before []dns.RR, ops []operation) (removed, added []dns.RR) {
+	after := applyToRecords(before, ops)
+	contains := func(rrs []dns.RR, rr dns.RR) bool {
+		for _, r := range rrs {
+			if dns.IsDuplicate(r, rr) && r.Header().Ttl == rr.Header().Ttl {
+				return true
+			}
+		}
+		return false
+	}
+
+	for _, rr := range before {
+		if !contains(after, rr) {
+			removed = append(removed, rr)
+		}
+	}
+	for _, rr := range after {
+		if !contains(before, rr) {
+			added = append(added, rr)
+		}
+	}
+	return removed, added
+}
+
+// logChanges logs the changes ops make to the RRsets in state as a diff,
+// with "-" for removed records and "+" for added ones.
+func logChanges(state *journalEntry, ops []operation) {
+	removed, added := recordChanges(state.before, ops)
+	for _, rr := range removed {
+		infof("- %s", rr)
+	}
+	for _, rr := range added {
+		infof("+ %s", rr)
+	}
+}
+
+// printResults prints the results of the updates made as JSON, with
+// -o json, if there were any.
+func printResults(exitcode int) {
+	if !jsonResults() || updateResults == nil {
+		return
+	}
+	enc := json.NewEncoder(os.Stdout)
+	enc.SetIndent("", "  ")
+	enc.Encode(struct {
+		Updates  []updateResult `json:"updates"`
+		ExitCode int            `json:"exit_code"`
+	}{updateResults, exitcode})
+}
+
+type reportRecord struct {
+	Zone   string `json:"zone"`
+	Record string `json:"record"`
+}
+
+// A report summarizes the records changed by all the updates made, for
+// --report-fd and --report-file.
+type report struct {
+	Created  []reportRecord `json:"created"`
+	Deleted  []reportRecord `json:"deleted"`
+	Skipped  []reportRecord `json:"skipped"`
+	Planned  []reportRecord `json:"planned"`
+	Failed   []reportRecord `json:"failed"`
+	ExitCode int            `json:"exit_code"`
+}
+
+// writeReport writes the report to the file descriptor given with
+// --report-fd or the file given with --report-file.
+func writeReport(exitcode int) {
+	var f *os.File
+	if file := viper.GetString("report-file"); len(file) > 0 {
+		var err error
+		if f, err = os.Create(file); err != nil {
+			log.Printf("Unable to write report: %s", err)
+			return
+		}
+	} else if fd := viper.GetInt("report-fd"); fd > 0 {
+		f = os.NewFile(uintptr(fd), "report")
+	} else {
+		return
+	}
+	defer f.Close()
+
+	rep := report{
+		Created:  []reportRecord{},
+		Deleted:  []reportRecord{},
+		Skipped:  []reportRecord{},
+		Planned:  []reportRecord{},
+		Failed:   []reportRecord{},
+		ExitCode: exitcode,
+	}
+	add := func(list *[]reportRecord, zone string, rrs []dns.RR) {
+		for _, rr := range rrs {
+			*list = append(*list, reportRecord{zone, rr.String()})
+		}
+	}
+	for _, res := range updateResults {
+		switch res.Status {
+		case "sent":
+			add(&rep.Deleted, res.Zone, res.removed)
+			add(&rep.Created, res.Zone, res.added)
+		case "unchanged":
+			for _, op := range res.Operations {
+				rep.Skipped = append(rep.Skipped, reportRecord{res.Zone, op.Record})
+			}
+		case "dry-run":
+			for _, op := range res.Operations {
+				rep.Planned = append(rep.Planned, reportRecord{res.Zone, op.Action + " " + op.Record})
+			}
+		default:
+			for _, op := range res.Operations {
+				rep.Failed = append(rep.Failed, reportRecord{res.Zone, op.Action + " " + op.Record})
+			}
+		}
+	}
+
+	enc := json.NewEncoder(f)
+	enc.SetIndent("", "  ")
+	if err := enc.Encode(rep); err != nil {
+		log.Printf("Unable to write report: %s", err)
+	}
+}
@@ -0,0 +1,538 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"fmt"
+	"log"
+	"net"
+	"sort"
+	"strconv"
+	"strings"
+
+	"github.com/miekg/dns"
+	"github.com/spf13/cast"
+	"github.com/spf13/viper"
+)
+
+// A reverseZone holds the reverse zone for the addresses in a prefix, from
+// the reverse-zones section of the config file.
+type reverseZone struct {
+	prefix *net.IPNet
+	zone   string
+}
+
+// The configured reverse zones, most specific prefix first.
+var reverseZones []reverseZone
+
+// readReverseZones reads the reverse-zones section of the config. Each
+// prefix maps to either the name of its reverse zone, or to the settings
+// for it: the zone name and, like in the zones section, the server and key
+// to use for it, which are added to the zone routes.
+func readReverseZones() {
+	reverseZones = nil
+	for prefix, value := range viper.GetStringMap("reverse-zones") {
+		_, ipnet, err := net.ParseCIDR(prefix)
+		if err != nil {
+			log.Panicf("Invalid prefix in reverse-zones: %s", err)
+		}
+
+		zone, err := cast.ToStringE(value)
+		if err != nil {
+			settings, merr := cast.ToStringMapStringE(value)
+			if merr != nil {
+				log.Panicf("Invalid reverse zone for %s: %s", prefix, err)
+			}
+			zone = settings["zone"]
+			delete(settings, "zone")
+			if len(zone) == 0 {
+				log.Panicf("Missing zone for %s in reverse-zones", prefix)
+			}
+			if alg, ok := settings["tsig-algorithm"]; ok {
+				if _, ok := lookupTsigAlgorithm(alg); !ok {
+					log.Panicf("Unknown tsig-algorithm for %s: %s", prefix, alg)
+				}
+			}
+			route := zoneRoutes[strings.ToLower(dns.Fqdn(zone))]
+			if route == nil {
+				route = make(map[string]string)
+				zoneRoutes[strings.ToLower(dns.Fqdn(zone))] = route
+			}
+			for key, value := range settings {
+				route[key] = value
+			}
+		}
+		if ones, bits := ipnet.Mask.Size(); bits == 128 && ones%4 != 0 {
+			log.Panicf("Invalid prefix in reverse-zones: %s: IPv6 reverse zones must be on a nibble (4 bit) boundary", prefix)
+		}
+		reverseZones = append(reverseZones, reverseZone{ipnet, strings.ToLower(dns.Fqdn(zone))})
+	}
+	sort.Slice(reverseZones, func(i, j int) bool {
+		a, _ := reverseZones[i].prefix.Mask.Size()
+		b, _ := reverseZones[j].prefix.Mask.Size()
+		return a > b
+	})
+}
+
+// isReverseName reports whether name is in a reverse zone: under
+// in-addr.arpa or ip6.arpa, or in one of the reverse-zones of the config.
+func isReverseName(name string) bool {
+	name = strings.ToLower(dns.Fqdn(name))
+	if dns.IsSubDomain("in-addr.arpa.", name) || dns.IsSubDomain("ip6.arpa.", name) {
+		return true
+	}
+	for _, rz := range reverseZones {
+		if dns.IsSubDomain(rz.zone, name) {
+			return true
+		}
+	}
+	return false
+}
+
+// hostLabels returns the labels for the part of ip below the given prefix
+// length, most specific first: the octets of IPv4 addresses and the
+// nibbles of IPv6 addresses, as in the standard reverse names.
+func hostLabels(ip net.IP, ones int) []string {
+	var labels []string
+	if ip4 := ip.To4(); ip4 != nil {
+		for i := 3; i >= ones/8; i-- {
+			labels = append(labels, strconv.Itoa(int(ip4[i])))
+		}
+		return labels
+	}
+	ip16 := ip.To16()
+	for i := 31; i >= ones/4; i-- {
+		nibble := ip16[i/2] >> 4
+		if i%2 == 1 {
+			nibble = ip16[i/2] & 0xf
+		}
+		labels = append(labels, strconv.FormatInt(int64(nibble), 16))
+	}
+	return labels
+}
+
+// ptrPrefixLen returns the ptr-prefix-len setting: the length of the IPv6
+// prefix PTR records are made for, or 128 for full addresses.
+func ptrPrefixLen() (int, error) {
+	n := viper.GetInt("ptr-prefix-len")
+	if n == 0 {
+		return 128, nil
+	} else if n < 0 || n > 128 || n%4 != 0 {
+		return 0, fmt.Errorf("invalid ptr-prefix-len %d: must be a multiple of 4 up to 128", n)
+	}
+	return n, nil
+}
+
+// reverseName returns the name of the PTR record for ip. For addresses in
+// the reverse-zones of the config, this is the part of the address below
+// the prefix (the last octet for a classless RFC 2317 zone) in the given
+// zone, which is also returned; for others, it is the standard
+// in-addr.arpa or ip6.arpa name, and the zone is left to be discovered.
+// With ptr-prefix-len, the nibbles of IPv6 addresses below that prefix
+// length (e.g. the interface ID) are left out, so all the addresses in
+// the prefix share a PTR record.
+func reverseName(ip net.IP) (string, string, error) {
+	v6 := ip.To4() == nil
+	cut := 0
+	if v6 {
+		n, err := ptrPrefixLen()
+		if err != nil {
+			return "", "", err
+		}
+		cut = (128 - n) / 4
+	}
+
+	for _, rz := range reverseZones {
+		if !rz.prefix.Contains(ip) || v6 != (rz.prefix.IP.To4() == nil) {
+			continue
+		}
+		ones, _ := rz.prefix.Mask.Size()
+		if v6 && ones > 128-cut*4 {
+			return "", "", fmt.Errorf("the reverse zone %s of %s is delegated below ptr-prefix-len %d",
+				rz.zone, rz.prefix, 128-cut*4)
+		}
+		labels := hostLabels(ip, ones)
+		if v6 {
+			labels = labels[cut:]
+		}
+		if len(labels) == 0 {
+			return rz.zone, rz.zone, nil
+		}
+		return strings.Join(labels, ".") + "." + rz.zone, rz.zone, nil
+	}
+
+	if v6 && cut > 0 {
+		return strings.Join(hostLabels(ip, 0)[cut:], ".") + ".ip6.arpa.", "", nil
+	}
+	name, err := dns.ReverseAddr(ip.String())
+	return name, "", err
+}
+
+func recordAddress(rr dns.RR) net.IP {
+	switch rr := rr.(type) {
+	case *dns.A:
+		return rr.A
+	case *dns.AAAA:
+		return rr.AAAA
+	}
+	return nil
+}
+
+// reverseOp returns the operation updating the PTR record matching the
+// address of an A or AAAA record, or nil if op has no address. The PTR
+// record points at the name of the record, or the name given with
+// --ptr-name.
+func reverseOp(op operation) *operation {
+	ip := recordAddress(op.rr)
+	if ip == nil {
+		return nil
+	}
+
+	name, zone, err := reverseName(ip)
+	if err != nil {
+		log.Printf("No PTR record for %s: %s", ip, err)
+		emit(Event{Type: EventPTRSkipped, Name: op.rr.Header().Name, Record: op.rr.String(), Err: err})
+		return nil
+	}
+	if len(zone) > 0 {
+		zoneCache[name] = zone
+	}
+
+	ptr := &dns.PTR{Hdr: dns.RR_Header{Name: name,
+		Ttl:    op.rr.Header().Ttl,
+		Rrtype: dns.TypePTR,
+		Class:  dns.ClassINET},
+		Ptr: dns.Fqdn(op.rr.Header().Name)}
+	if target := viper.GetString("ptr-name"); len(target) > 0 {
+		ptr.Ptr = strings.ToLower(expandName(target))
+	}
+
+	// Appending a record appends to the PTR RRset too, so that the PTR
+	// records of other names sharing the address are kept
+	switch op.action {
+	case "replace", "append":
+		return &operation{op.action, ptr}
+	case "delete", "remove":
+		return &operation{"delete", ptr}
+	}
+	return nil
+}
+
+// staleReverseOps returns the operations removing the PTR records of the
+// addresses that replacing the A and AAAA records in ops removes. Only the
+// PTR records pointing back at the name are removed.
+func staleReverseOps(ops []operation) []operation {
+	var keys []string
+	replaced := make(map[string]map[string]bool)
+	for _, op := range ops {
+		ip := recordAddress(op.rr)
+		if op.action != "replace" || ip == nil {
+			continue
+		}
+		key := rrsetKey(op.rr)
+		if replaced[key] == nil {
+			replaced[key] = make(map[string]bool)
+			keys = append(keys, key)
+		}
+		replaced[key][ip.String()] = true
+	}
+
+	var rops []operation
+	for _, key := range keys {
+		rrs, err := currentRRset(key)
+		if err != nil {
+			log.Printf("Unable to look up the old addresses of %s: %s", key, err)
+			continue
+		}
+		for _, rr := range rrs {
+			ip := recordAddress(rr)
+			if ip == nil || replaced[key][ip.String()] {
+				continue
+			}
+			if rop := reverseOp(operation{"remove", rr}); rop != nil {
+				rops = append(rops, operation{"remove", rop.rr})
+			}
+		}
+	}
+	return rops
+}
+
+// reverseUpdates returns the updates of the PTR records for the addresses
+// in ops, including the removal of those of addresses that are replaced.
+func reverseUpdates(ops []operation) []*zoneUpdate {
+	rops := staleReverseOps(ops)
+	for _, op := range ops {
+		if rop := reverseOp(op); rop != nil {
+			rops = append(rops, *rop)
+		}
+	}
+	return groupByZone(rops)
+}
+
+// sendUpdates sends updates in order, stopping at the first failure. If
+// capture is set, the state before each update is recorded first, and the
+// states of the updates that were applied are returned for rollback. The
+// result of each update is recorded in it.
+func sendUpdates(updates []*zoneUpdate, capture bool) ([]*journalEntry, error) {
+	var applied []*journalEntry
+	for _, u := range updates {
+		var state *journalEntry
+		if capture {
+			var err error
+			if state, err = captureState(u.zone, u.ops); err != nil {
+				u.err = fmt.Errorf("unable to look up records in %s: %w", u.zone, err)
+				return applied, u.err
+			}
+		}
+		if err := update(u.zone, u.ops); err != nil {
+			u.err = err
+			return applied, err
+		}
+		u.sent = true
+		if state != nil {
+			applied = append(applied, state)
+		}
+	}
+	return applied, nil
+}
+
+// Set while rollback restores the records from before a failed change:
+// its updates aren't checked against the guards, and are still sent after
+// the updates were cancelled.
+var rollingBack bool
+
+// rollback restores the recorded states, newest first.
+func rollback(states []*journalEntry) error {
+	journalDisabled, rollingBack = true, true
+	defer func() { journalDisabled, rollingBack = false, false }()
+
+	for i := len(states) - 1; i >= 0; i-- {
+		ops, err := states[i].undoOps()
+		if err == nil {
+			err = update(states[i].Zone, ops)
+		}
+		if err != nil {
+			return fmt.Errorf("unable to roll back %s: %s", strings.Join(states[i].RRsets, ", "), err)
+		}
+		infof("Rolled back %s", strings.Join(states[i].RRsets, ", "))
+	}
+	return nil
+}
+
+// hasAddress reports whether name currently has an address record for ip.
+func hasAddress(name string, ip net.IP) bool {
+	rrtype := dns.TypeA
+	if ip.To4() == nil {
+		rrtype = dns.TypeAAAA
+	}
+	rrs, err := lookup(name, rrtype)
+	if err != nil {
+		return false
+	}
+	for _, rr := range rrs {
+		if addr := recordAddress(rr); addr != nil && addr.Equal(ip) {
+			return true
+		}
+	}
+	return false
+}
+
+// ptrConflicts checks the PTR records written for the addresses in ops
+// for existing PTR records pointing at other names. Replacing those would
+// take over the reverse entry of another host, which is an error unless
+// --force is given; appending next to them is only warned about. PTR
+// records whose names no longer have the address are reported as
+// dangling.
+func ptrConflicts(ops []operation) error {
+	for _, op := range ops {
+		rop := reverseOp(op)
+		if rop == nil || (rop.action != "replace" && rop.action != "append") {
+			continue
+		}
+		ptr := rop.rr.(*dns.PTR)
+		ip := recordAddress(op.rr)
+
+		rrs, err := lookup(ptr.Hdr.Name, dns.TypePTR)
+		if err != nil {
+			return fmt.Errorf("unable to check the PTR records of %s: %w", ip, err)
+		}
+		for _, rr := range rrs {
+			old, ok := rr.(*dns.PTR)
+			if !ok || strings.EqualFold(old.Ptr, ptr.Ptr) {
+				continue
+			}
+			msg := fmt.Sprintf("the PTR record of %s already points at %s", ip, old.Ptr)
+			if !hasAddress(old.Ptr, ip) {
+				msg += ", which no longer has that address"
+			}
+			if rop.action == "append" || viper.GetBool("force") {
+				log.Printf("Warning: %s", msg)
+				continue
+			}
+			return fmt.Errorf("%s (use --force to replace it)", msg)
+		}
+	}
+	return nil
+}
+
+// reportReverse logs the result of the update of each PTR record.
+func reportReverse(updates []*zoneUpdate) {
+	for _, u := range updates {
+		for _, op := range u.ops {
+			ptr, ok := op.rr.(*dns.PTR)
+			if !ok {
+				continue
+			}
+			event := Event{Zone: u.zone, Name: ptr.Ptr, Record: ptr.String(), Err: u.err}
+			switch {
+			case u.sent:
+				infof("PTR %s (%s): updated", ptr.Hdr.Name, ptr.Ptr)
+				event.Type = EventPTRUpdated
+			case u.err != nil:
+				log.Printf("PTR %s (%s): failed: %s", ptr.Hdr.Name, ptr.Ptr, u.err)
+				event.Type = EventPTRFailed
+			default:
+				log.Printf("PTR %s (%s): not sent", ptr.Hdr.Name, ptr.Ptr)
+				event.Type = EventPTRSkipped
+			}
+			emit(event)
+		}
+	}
+}
+
+// updateWithReverse sends the update of zone and the updates of the
+// matching PTR records (see sendWithReverse).
+func updateWithReverse(zone string, ops []operation) error {
+	if err := ptrConflicts(ops); err != nil {
+		return err
+	}
+	return sendWithReverse([]*zoneUpdate{{zone: zone, ops: ops}}, reverseUpdates(ops))
+}
+
+// sendWithReverse sends the forward updates and the reverse updates of
+// their PTR records. Normally the forward updates are sent first, and a
+// failed PTR update leaves them in place. With --rollback, the forward
+// updates are rolled back if a PTR update fails. With --reverse-required,
+// the PTR records are updated first, and the forward updates are only sent
+// if that succeeds; if they then fail, the PTR updates are rolled back.
+// The updates of several zones on either side are a transaction, as with
+// sendTransaction.
+func sendWithReverse(forward, reverse []*zoneUpdate) error {
+	unitLock.Lock()
+	defer unitLock.Unlock()
+
+	first, second := forward, reverse
+	firstName, secondName := "forward", "reverse"
+	if viper.GetBool("reverse-required") {
+		first, second = reverse, forward
+		firstName, secondName = "reverse", "forward"
+	}
+	undoFirst := viper.GetBool("rollback") || viper.GetBool("reverse-required")
+	transaction := func(updates []*zoneUpdate) bool {
+		return len(updates) > 1 && !viper.GetBool("dry-run")
+	}
+	defer reportReverse(reverse)
+
+	applied, err := sendUpdates(first, undoFirst || transaction(first))
+	if err != nil {
+		if rerr := rollback(applied); rerr != nil {
+			log.Print(rerr)
+		}
+		return fmt.Errorf("%s update failed: %w", firstName, err)
+	}
+
+	done, err := sendUpdates(second, transaction(second))
+	if err == nil {
+		return nil
+	}
+	rerr := rollback(done)
+	if !undoFirst {
+		if rerr != nil {
+			return &partialError{fmt.Errorf("%s update failed: %s; %s", secondName, err, rerr)}
+		}
+		return &partialError{fmt.Errorf("%s update failed, the %s update was kept: %w", secondName, firstName, err)}
+	}
+	if ferr := rollback(applied); rerr == nil {
+		rerr = ferr
+	}
+	if rerr != nil {
+		return &partialError{fmt.Errorf("%s update failed: %s; %s", secondName, err, rerr)}
+	}
+	return fmt.Errorf("%s update failed, the %s update was rolled back: %w", secondName, firstName, err)
+}
+
+// reverseOnly updates only the PTR record of an address, leaving the
+// forward zone alone: --reverse-only <address> <name> [ttl]. With
+// --delete, the name can be left out.
+func reverseOnly(args []string) int {
+	action := flagAction()
+	if len(args) < 1 || len(args) > 3 || (len(args) < 2 && action != "delete") {
+		log.Print("Usage: update-dns --reverse-only <address> <name> [ttl]")
+		return 1
+	}
+	ip := net.ParseIP(args[0])
+	if ip == nil {
+		log.Printf("Invalid address: %s", args[0])
+		return 1
+	}
+
+	var op *operation
+	if len(args) == 1 {
+		name, zone, err := reverseName(ip)
+		if err != nil {
+			log.Printf("Invalid address: %s", err)
+			return 1
+		}
+		if len(zone) > 0 {
+			zoneCache[name] = zone
+		}
+		op = &operation{"delete", &dns.PTR{Hdr: dns.RR_Header{Name: name,
+			Rrtype: dns.TypePTR, Class: dns.ClassINET}}}
+	} else {
+		rrtype := "A"
+		if ip.To4() == nil {
+			rrtype = "AAAA"
+		}
+		record := fmt.Sprintf("%s %s %s %s", args[1], strings.Join(args[2:], " "), rrtype, ip)
+		rr, err := parseRecord(record, action)
+		if err != nil {
+			log.Printf("Unable to parse record: %s", err)
+			return 1
+		}
+		if err := ptrConflicts([]operation{{action, rr}}); err != nil {
+			log.Printf("Not sending update: %s", err)
+			return 1
+		}
+		if _, _, err := reverseName(ip); err != nil {
+			log.Printf("No PTR record for %s: %s", ip, err)
+			return exitConfig
+		}
+		op = reverseOp(operation{action, rr})
+	}
+
+	zone := getZone(op.rr.Header().Name)
+	ops := []operation{*op}
+	if err := checkGuards(zone, ops, true); err != nil {
+		log.Printf("Not sending update: %s", err)
+		return 1
+	}
+	if err := update(zone, ops); err != nil {
+		log.Printf("Unable to send update: %s", err)
+		return exitCode(err)
+	}
+	infof("Updated %s", op.rr.Header().Name)
+	return 0
+}
@@ -0,0 +1,92 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"net"
+	"testing"
+)
+
+func TestReverseName(t *testing.T) {
+	testConfig(t, map[string]interface{}{"reverse-zones": map[string]interface{}{
+		"192.0.2.128/26": "128/26.2.0.192.in-addr.arpa",
+		"2001:db8::/48":  "8.b.d.0.1.0.0.2.ip6.arpa",
+	}})
+	tests := []struct {
+		ip   string
+		name string
+		zone string
+	}{
+		{"192.0.2.1", "1.2.0.192.in-addr.arpa.", ""},
+		// RFC 2317: the classless zone has a name for each address
+		{"192.0.2.130", "130.128/26.2.0.192.in-addr.arpa.", "128/26.2.0.192.in-addr.arpa."},
+		{"2001:db8::1", "1.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.8.b.d.0.1.0.0.2.ip6.arpa.",
+			"8.b.d.0.1.0.0.2.ip6.arpa."},
+		{"2001:db9::1", "1.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.9.b.d.0.1.0.0.2.ip6.arpa.", ""},
+	}
+	for _, test := range tests {
+		name, zone, err := reverseName(net.ParseIP(test.ip))
+		if err != nil {
+			t.Errorf("reverseName(%s): %v", test.ip, err)
+		} else if name != test.name || zone != test.zone {
+			t.Errorf("reverseName(%s) = %s, %q, want %s, %q", test.ip, name, zone, test.name, test.zone)
+		}
+	}
+}
+
+func TestReverseNamePrefixLen(t *testing.T) {
+	zones := map[string]interface{}{
+		"2001:db8::/48":   "8.b.d.0.1.0.0.2.ip6.arpa",
+		"2001:db8:1::/72": "0.0.0.0.0.0.1.0.0.0.8.b.d.0.1.0.0.2.ip6.arpa",
+	}
+	tests := []struct {
+		ip        string
+		prefixLen int
+		name      string
+		zone      string
+	}{
+		{"2001:db8:0:1::1", 64, "1.0.0.0.8.b.d.0.1.0.0.2.ip6.arpa.", "8.b.d.0.1.0.0.2.ip6.arpa."},
+		{"2001:db9:0:1::1", 64, "1.0.0.0.9.b.d.0.1.0.0.2.ip6.arpa.", ""},
+		{"2001:db8::1", 48, "8.b.d.0.1.0.0.2.ip6.arpa.", "8.b.d.0.1.0.0.2.ip6.arpa."},
+		// IPv4 addresses are not affected
+		{"192.0.2.1", 64, "1.2.0.192.in-addr.arpa.", ""},
+	}
+	for _, test := range tests {
+		testConfig(t, map[string]interface{}{"reverse-zones": zones, "ptr-prefix-len": test.prefixLen})
+		name, zone, err := reverseName(net.ParseIP(test.ip))
+		if err != nil {
+			t.Errorf("reverseName(%s): %v", test.ip, err)
+		} else if name != test.name || zone != test.zone {
+			t.Errorf("reverseName(%s) with ptr-prefix-len %d = %s, %q, want %s, %q",
+				test.ip, test.prefixLen, name, zone, test.name, test.zone)
+		}
+	}
+
+	// Zones delegated below ptr-prefix-len can't hold the PTR record, and
+	// the prefix length has to be a whole number of nibbles
+	for _, test := range []struct {
+		ip        string
+		prefixLen int
+	}{
+		{"2001:db8:1::1", 64},
+		{"2001:db8::1", 62},
+	} {
+		testConfig(t, map[string]interface{}{"reverse-zones": zones, "ptr-prefix-len": test.prefixLen})
+		if name, _, err := reverseName(net.ParseIP(test.ip)); err == nil {
+			t.Errorf("reverseName(%s) with ptr-prefix-len %d = %s, want an error", test.ip, test.prefixLen, name)
+		}
+	}
+}
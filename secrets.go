@@ -0,0 +1,142 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"log"
+	"os"
+	"strings"
+
+	"github.com/miekg/dns"
+	"github.com/spf13/cast"
+)
+
+// The service name TSIG secrets are stored under in the keyring, with the
+// key name as the user name.
+const keyringService = "update-dns"
+
+// Secrets read from files or the keyring so far, so that each is only
+// read once.
+var secretCache = make(map[string]string)
+
+func readSecretFile(file string) string {
+	if secret, ok := secretCache[file]; ok {
+		return secret
+	}
+
+	data, err := os.ReadFile(file)
+	if err != nil {
+		log.Panicf("Unable to read tsig-secret-file: %s", err)
+	}
+	secret := strings.TrimSpace(string(data))
+	secretCache[file] = secret
+	return secret
+}
+
+// Keys read from BIND key files so far.
+var keyFiles = make(map[string][]bindKey)
+
+// keyFileKey returns the key to use for name from the BIND key file named
+// by tsig-key-file, or nil if there is none. If the file contains several
+// keys, tsig-name selects one of them.
+func keyFileKey(name string) *bindKey {
+	file := setting(name, "tsig-key-file")
+	if len(file) == 0 {
+		return nil
+	}
+
+	keys, ok := keyFiles[file]
+	if !ok {
+		var err error
+		if keys, err = readBindKeys(file); err != nil {
+			log.Panicf("Unable to read tsig-key-file: %s", err)
+		}
+		keyFiles[file] = keys
+	}
+
+	keyName := setting(name, "tsig-name")
+	if len(keyName) == 0 {
+		if len(keys) > 1 {
+			log.Panicf("%s contains several keys; select one with tsig-name", file)
+		}
+		return &keys[0]
+	}
+	for i := range keys {
+		if strings.EqualFold(keys[i].name, dns.Fqdn(keyName)) {
+			return &keys[i]
+		}
+	}
+	log.Panicf("Key %s not found in %s", keyName, file)
+	return nil
+}
+
+// sourceKey returns the key to use for name from a BIND key file or Vault,
+// or nil if neither is configured.
+func sourceKey(name string) *bindKey {
+	if key := keyFileKey(name); key != nil {
+		return key
+	}
+	return vaultKey(name)
+}
+
+// tsigName returns the name of the TSIG key to use for name.
+func tsigName(name string) string {
+	if keyName := setting(name, "tsig-name"); len(keyName) > 0 {
+		return keyName
+	}
+	if key := sourceKey(name); key != nil {
+		return key.name
+	}
+	return ""
+}
+
+// tsigSecret returns the TSIG secret to use for name. It is either given
+// directly in the config, taken from the environment variable named by
+// tsig-secret-env, read from the file named by tsig-secret-file, taken from
+// the BIND key file named by tsig-key-file or the Vault secret named by
+// tsig-vault-path, or looked up in the platform keyring if
+// tsig-secret-keyring is set.
+func tsigSecret(name string) string {
+	if secret := setting(name, "tsig-secret"); len(secret) > 0 {
+		return secret
+	}
+	if env := setting(name, "tsig-secret-env"); len(env) > 0 {
+		secret, ok := os.LookupEnv(env)
+		if !ok {
+			log.Panicf("tsig-secret-env: %s is not set", env)
+		}
+		return strings.TrimSpace(secret)
+	}
+	if file := setting(name, "tsig-secret-file"); len(file) > 0 {
+		return readSecretFile(file)
+	}
+	if key := sourceKey(name); key != nil {
+		return key.secret
+	}
+	if cast.ToBool(setting(name, "tsig-secret-keyring")) {
+		keyName := tsigName(name)
+		if secret, ok := secretCache["keyring:"+keyName]; ok {
+			return secret
+		}
+		secret, err := keyringGet(keyName)
+		if err != nil {
+			log.Panicf("Unable to get the secret for %s from the keyring: %s", keyName, err)
+		}
+		secretCache["keyring:"+keyName] = secret
+		return secret
+	}
+	return ""
+}
@@ -0,0 +1,247 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"crypto/subtle"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"log"
+	"net"
+	"net/http"
+	"strconv"
+	"strings"
+	"sync"
+
+	"github.com/miekg/dns"
+	"github.com/spf13/viper"
+)
+
+// The request body for creating, replacing and appending records.
+type apiRecords struct {
+	TTL  uint32   `json:"ttl"`
+	Data []string `json:"data"`
+}
+
+type apiServer struct {
+	tokens []string
+
+	// The update code is not safe for concurrent use
+	lock sync.Mutex
+}
+
+func writeJSON(w http.ResponseWriter, status int, v interface{}) {
+	w.Header().Set("Content-Type", "application/json")
+	w.WriteHeader(status)
+	json.NewEncoder(w).Encode(v)
+}
+
+func writeError(w http.ResponseWriter, status int, err error) {
+	writeJSON(w, status, map[string]string{"error": err.Error()})
+}
+
+func (s *apiServer) authorized(r *http.Request) bool {
+	auth := r.Header.Get("Authorization")
+	if !strings.HasPrefix(auth, "Bearer ") {
+		return false
+	}
+	token := []byte(strings.TrimPrefix(auth, "Bearer "))
+	for _, t := range s.tokens {
+		if subtle.ConstantTimeCompare(token, []byte(t)) == 1 {
+			return true
+		}
+	}
+	return false
+}
+
+// parsePath splits /records/<name>[/<type>] into the name and type.
+func parsePath(path string) (string, uint16, error) {
+	parts := strings.Split(strings.Trim(path, "/"), "/")
+	if len(parts) < 2 || len(parts) > 3 || parts[0] != "records" || len(parts[1]) == 0 {
+		return "", 0, fmt.Errorf("expected /records/<name>[/<type>]")
+	}
+
+	var rrtype uint16
+	if len(parts) == 3 {
+		t, err := parseType(parts[2])
+		if err != nil {
+			return "", 0, err
+		}
+		rrtype = t
+	}
+	return dns.Fqdn(parts[1]), rrtype, nil
+}
+
+// recordOps builds one operation per value in the request body.
+func recordOps(r *http.Request, action, name string, rrtype uint16) ([]operation, error) {
+	var body apiRecords
+	if err := json.NewDecoder(r.Body).Decode(&body); err != nil {
+		return nil, fmt.Errorf("invalid request body: %s", err)
+	} else if len(body.Data) == 0 {
+		return nil, fmt.Errorf("no data given")
+	}
+
+	var ops []operation
+	for _, data := range body.Data {
+		record := strings.Join([]string{name, strconv.Itoa(int(body.TTL)),
+			dns.TypeToString[rrtype], data}, " ")
+		rr, err := parseRecord(record, action)
+		if err != nil {
+			return nil, fmt.Errorf("invalid record '%s': %s", data, err)
+		}
+		ops = append(ops, operation{action, rr})
+	}
+	return ops, nil
+}
+
+func (s *apiServer) ServeHTTP(w http.ResponseWriter, r *http.Request) {
+	if !s.authorized(r) {
+		writeError(w, http.StatusUnauthorized, fmt.Errorf("unauthorized"))
+		return
+	}
+
+	name, rrtype, err := parsePath(r.URL.Path)
+	if err != nil {
+		writeError(w, http.StatusNotFound, err)
+		return
+	}
+
+	s.lock.Lock()
+	defer s.lock.Unlock()
+	updateResults = nil // only the results of the last request are kept
+
+	if r.Method == http.MethodGet {
+		qtype := rrtype
+		if qtype == 0 {
+			qtype = dns.TypeANY
+		}
+		rrs, err := lookup(name, qtype)
+		if err != nil {
+			writeError(w, httpStatus(err), err)
+			return
+		}
+		records := make([]jsonRecord, 0, len(rrs))
+		for _, rr := range rrs {
+			records = append(records, toJSONRecord(rr))
+		}
+		writeJSON(w, http.StatusOK, records)
+		return
+	}
+
+	var ops []operation
+	switch r.Method {
+	case http.MethodDelete:
+		record := name
+		if rrtype != 0 {
+			record += " " + dns.TypeToString[rrtype]
+		}
+		rr, err := parseRecord(record, "delete")
+		if err != nil {
+			writeError(w, http.StatusBadRequest, err)
+			return
+		}
+		ops = []operation{{"delete", rr}}
+	case http.MethodPost, http.MethodPut, http.MethodPatch:
+		if rrtype == 0 {
+			writeError(w, http.StatusNotFound, fmt.Errorf("missing record type"))
+			return
+		}
+		action := map[string]string{
+			http.MethodPost:  "append",
+			http.MethodPut:   "replace",
+			http.MethodPatch: "append",
+		}[r.Method]
+		ops, err = recordOps(r, action, name, rrtype)
+		if err != nil {
+			writeError(w, http.StatusBadRequest, err)
+			return
+		}
+		if r.Method == http.MethodPost {
+			// Creating fails if the RRset already exists
+			ops = append([]operation{{"nxrrset", ops[0].rr}}, ops...)
+		}
+	default:
+		writeError(w, http.StatusMethodNotAllowed, fmt.Errorf("method not allowed"))
+		return
+	}
+
+	span := startRequestSpan(r.Method+" "+r.URL.Path, r.Header.Get("traceparent"),
+		"http.method", r.Method, "http.target", r.URL.Path)
+	err = applyOps(ops)
+	span.finish(err)
+	if err != nil {
+		log.Printf("%s %s failed: %s", r.Method, r.URL.Path, err)
+		writeError(w, httpStatus(err), err)
+		return
+	}
+	infof("%s %s: ok", r.Method, r.URL.Path)
+	writeJSON(w, http.StatusOK, map[string]string{"status": "ok"})
+}
+
+// httpStatus returns the status of a request whose update failed with err,
+// by the kind of the error (as exitCode does for the exit status).
+func httpStatus(err error) int {
+	switch Kind(err) {
+	case ErrValidation:
+		return http.StatusForbidden
+	case ErrTSIG:
+		return http.StatusUnauthorized
+	case ErrRcode:
+		rcode, _ := Rcode(err)
+		switch rcode {
+		case dns.RcodeNotAuth, dns.RcodeRefused:
+			return http.StatusForbidden
+		case dns.RcodeYXDomain, dns.RcodeYXRrset, dns.RcodeNXRrset, dns.RcodeNameError:
+			return http.StatusPreconditionFailed
+		}
+	case ErrResolution:
+		return http.StatusNotFound
+	case ErrConfig:
+		return http.StatusInternalServerError
+	case ErrCancelled:
+		return http.StatusServiceUnavailable
+	case ErrTransport:
+		var nerr net.Error
+		if errors.As(err, &nerr) && nerr.Timeout() {
+			return http.StatusGatewayTimeout
+		}
+	}
+	return http.StatusBadGateway
+}
+
+func serve(args []string) int {
+	if len(args) != 0 {
+		log.Print("Usage: update-dns serve [--listen <address>]")
+		return 1
+	}
+
+	s := &apiServer{tokens: viper.GetStringSlice("serve.tokens")}
+	if len(s.tokens) == 0 {
+		log.Print("No API tokens configured (serve.tokens)")
+		return 1
+	}
+
+	sharedConn = new(connCache)
+	defer sharedConn.close()
+	tracing = len(tracingEndpoint()) > 0
+	signalExitcode = 0
+
+	infof("Listening on %s", viper.GetString("serve.listen"))
+	err := http.ListenAndServe(viper.GetString("serve.listen"), s)
+	log.Printf("HTTP server failed: %s", err)
+	return 1
+}
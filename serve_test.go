@@ -0,0 +1,64 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"errors"
+	"net/http"
+	"testing"
+
+	"github.com/miekg/dns"
+)
+
+// The error of applyOps keeps the kind of the first zone that failed, for
+// the status of the API request.
+func TestApplyOpsStatus(t *testing.T) {
+	b := useMockBackend(t, nil)
+	zoneCache["www.example.org."] = "example.org."
+	zoneCache["www.example.net."] = "example.net."
+	b.refuse["example.org."] = true
+	b.refuse["example.net."] = true
+	ops := []operation{
+		{"append", mustRR(t, "www.example.org. 300 IN A 192.0.2.1")},
+		{"append", mustRR(t, "www.example.net. 300 IN A 192.0.2.1")},
+	}
+
+	err := applyOps(ops)
+	if Kind(err) != ErrRcode {
+		t.Fatalf("got error %v of kind %s, want %s", err, Kind(err), ErrRcode)
+	}
+	if status := httpStatus(err); status != http.StatusForbidden {
+		t.Errorf("got status %d, want %d", status, http.StatusForbidden)
+	}
+}
+
+func TestHTTPStatus(t *testing.T) {
+	tests := []struct {
+		err    error
+		status int
+	}{
+		{kindError(ErrValidation, errors.New("risky change")), http.StatusForbidden},
+		{&rcodeError{dns.RcodeYXRrset}, http.StatusPreconditionFailed},
+		{&tsigError{code: dns.RcodeBadKey}, http.StatusUnauthorized},
+		{kindError(ErrResolution, errors.New("no zone")), http.StatusNotFound},
+		{errors.New("other"), http.StatusBadGateway},
+	}
+	for _, test := range tests {
+		if status := httpStatus(test.err); status != test.status {
+			t.Errorf("httpStatus(%v) = %d, want %d", test.err, status, test.status)
+		}
+	}
+}
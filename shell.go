@@ -0,0 +1,184 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"fmt"
+	"io"
+	"log"
+	"os"
+	"sort"
+	"strings"
+
+	"github.com/miekg/dns"
+	"golang.org/x/term"
+)
+
+var shellCommands = []string{"add", "answer", "class", "delete", "help",
+	"key", "prereq", "query", "quit", "send", "server", "show", "ttl",
+	"update", "zone"}
+
+const shellHelp = `Commands:
+  query <name> [type]             show records on the server
+  update add <record>             queue adding a record
+  update delete <name> [type [data]]
+                                  queue deleting records
+  prereq nxdomain|yxdomain|nxrrset|yxrrset ...
+                                  queue a prerequisite
+  show                            show the queued update
+  send                            send the queued update
+  answer                          show the last answer from the server
+  server, zone, key, ttl, class   as in nsupdate
+  quit                            leave the shell (discarding queued updates)
+`
+
+func recordTypes() []string {
+	types := make([]string, 0, len(dns.StringToType))
+	for t := range dns.StringToType {
+		types = append(types, t)
+	}
+	sort.Strings(types)
+	return types
+}
+
+// completeWord returns the longest common prefix of the candidates
+// starting with word.
+func completeWord(word string, candidates []string) string {
+	var match string
+	found := false
+	for _, c := range candidates {
+		if !strings.HasPrefix(strings.ToLower(c), strings.ToLower(word)) {
+			continue
+		}
+		if !found {
+			match, found = c, true
+			continue
+		}
+		for !strings.HasPrefix(c, match) {
+			match = match[:len(match)-1]
+		}
+	}
+	return match
+}
+
+// shellComplete completes commands for the first word on the line, and
+// record types for the words after it.
+func shellComplete(line string, pos int, key rune) (string, int, bool) {
+	if key != '\t' {
+		return "", 0, false
+	}
+
+	head := line[:pos]
+	start := strings.LastIndexAny(head, " \t") + 1
+	word := head[start:]
+
+	candidates := shellCommands
+	if len(strings.TrimSpace(head[:start])) > 0 {
+		candidates = recordTypes()
+	}
+
+	completion := completeWord(word, candidates)
+	if len(completion) <= len(word) {
+		return "", 0, false
+	}
+	return head[:start] + completion + line[pos:], start + len(completion), true
+}
+
+// safeExecute runs a line in the session, turning fatal errors into
+// ordinary ones so they don't end the shell.
+func safeExecute(s *nsupdateSession, line string) (quit bool, err error) {
+	defer recoverError(&err)
+
+	fields := strings.Fields(line)
+	if len(fields) > 0 {
+		switch strings.ToLower(fields[0]) {
+		case "query":
+			name, qtype, err := parseQueryArgs(fields[1:])
+			if err != nil {
+				return false, err
+			}
+			rrs, err := lookup(name, qtype)
+			if err != nil {
+				return false, err
+			}
+			printRecords(s.out, rrs)
+			return false, nil
+		case "help":
+			fmt.Fprint(s.out, shellHelp)
+			return false, nil
+		}
+	}
+	return s.execute(line)
+}
+
+func shell(args []string) int {
+	if len(args) != 0 {
+		log.Print("Usage: update-dns shell")
+		return 1
+	}
+
+	fd := int(os.Stdin.Fd())
+	if !term.IsTerminal(fd) {
+		log.Print("The shell needs a terminal; use 'update-dns nsupdate' for scripts")
+		return 1
+	}
+
+	state, err := term.MakeRaw(fd)
+	if err != nil {
+		log.Printf("Unable to set up terminal: %s", err)
+		return 1
+	}
+	defer term.Restore(fd, state)
+
+	t := term.NewTerminal(struct {
+		io.Reader
+		io.Writer
+	}{os.Stdin, os.Stdout}, "update-dns> ")
+	t.AutoCompleteCallback = shellComplete
+
+	log.SetOutput(t)
+	defer log.SetOutput(os.Stderr)
+
+	sharedConn = new(connCache)
+	defer sharedConn.close()
+
+	// Like a shell, exit with the status of the last command
+	exitcode := 0
+	s := &nsupdateSession{out: t, interactive: true}
+	for {
+		line, err := t.ReadLine()
+		if err == io.EOF {
+			break
+		} else if err != nil {
+			log.Printf("Unable to read input: %s", err)
+			return 1
+		}
+
+		quit, err := safeExecute(s, line)
+		exitcode = ExitCode(err)
+		if err != nil {
+			fmt.Fprintf(t, "Error: %s\n", err)
+		}
+		if quit {
+			break
+		}
+	}
+
+	if len(s.ops) > 0 {
+		log.Printf("Discarding %d unsent operations", len(s.ops))
+	}
+	return exitcode
+}
@@ -0,0 +1,142 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"log"
+	"os"
+	"os/signal"
+	"sync"
+	"syscall"
+	"time"
+)
+
+// The context cancelling the updates: by SIGINT, SIGTERM or the --timeout
+// in the command line tool (with cancelUpdates), or as set with
+// Updater.SetContext.
+var (
+	cancelCtx     = context.Background()
+	cancelUpdates = func() {}
+)
+
+// Held by sendUpdate while an update is made, until its result is written
+// to the journal and audit log.
+var updateLock sync.Mutex
+
+// Held while the updates of a transaction, or an update and those of its
+// PTR records, are sent and (if one fails) rolled back, so the program
+// doesn't exit between them.
+var unitLock sync.Mutex
+
+// The exit status after a signal, and the functions to call before
+// exiting, set by the daemon and serve commands so stopping them is a
+// normal exit
+var (
+	signalExitcode = exitCancelled
+	atExit         []func()
+	exitOnce       sync.Once
+)
+
+// handleSignals makes the first SIGINT or SIGTERM stop the program (see
+// stop). A second signal exits at once.
+func handleSignals() {
+	ctx, cancel := context.WithCancel(context.Background())
+	cancelCtx, cancelUpdates = ctx, cancel
+
+	signals := make(chan os.Signal, 2)
+	signal.Notify(signals, os.Interrupt, syscall.SIGTERM)
+	go func() {
+		sig := <-signals
+		go stop(signalExitcode, fmt.Sprintf("Received %s", sig))
+		<-signals
+		log.Print("Exiting without waiting for the update")
+		os.Exit(exitCancelled)
+	}()
+}
+
+// stop makes the program exit with exitcode once the update in progress (if
+// any) is done, rather than killing it half way through a change. The rest
+// of the updates aren't sent, and the results, report and metrics are
+// written as usual. In a transaction, the updates already made are rolled
+// back before exiting.
+func stop(exitcode int, reason string) {
+	cancelUpdates()
+	waiting := false
+	for _, lock := range []*sync.Mutex{&unitLock, &updateLock} {
+		if lock.TryLock() {
+			continue
+		}
+		if !waiting {
+			log.Printf("%s; exiting when the update in progress is done", reason)
+			waiting = true
+		}
+		lock.Lock()
+	}
+	if !waiting {
+		log.Print(reason)
+	}
+	exit(exitcode)
+}
+
+// exit writes the results, report and metrics of the command, and exits
+// with exitcode. Only the first call does: the program exits before the
+// others return.
+func exit(exitcode int) {
+	exitOnce.Do(func() {
+		for _, f := range atExit {
+			f()
+		}
+		printResults(exitcode)
+		writeReport(exitcode)
+		if len(command) > 0 {
+			writeMetrics(command, exitcode)
+		}
+		os.Exit(exitcode)
+	})
+}
+
+// cancelled returns an ErrCancelled error if the updates have been
+// cancelled, and nil otherwise.
+func cancelled() error {
+	if cancelCtx.Err() == nil {
+		return nil
+	}
+	return kindError(ErrCancelled, errors.New("Cancelled"))
+}
+
+// sleep waits for d, or until the updates are cancelled, and returns
+// cancelled().
+func sleep(d time.Duration) error {
+	t := time.NewTimer(d)
+	defer t.Stop()
+	select {
+	case <-t.C:
+	case <-cancelCtx.Done():
+	}
+	return cancelled()
+}
+
+// SetContext makes the updater stop when ctx is cancelled: an update
+// already sent is finished, to not leave a change half made, but no more
+// updates are sent, and the calls return an ErrCancelled error.
+func (u *Updater) SetContext(ctx context.Context) {
+	apiLock.Lock()
+	defer apiLock.Unlock()
+	cancelCtx = ctx
+}
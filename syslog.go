@@ -0,0 +1,59 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//go:build !windows && !plan9
+// +build !windows,!plan9
+
+package updatedns
+
+import (
+	"fmt"
+	"log/syslog"
+	"sort"
+)
+
+func newSyslogBackend() (logBackend, error) {
+	w, err := syslog.New(syslog.LOG_DAEMON|syslog.LOG_INFO, "update-dns")
+	if err != nil {
+		return nil, err
+	}
+	return &syslogBackend{w}, nil
+}
+
+// syslogBackend sends log messages to syslog, with the fields of update
+// results appended as key=value pairs.
+type syslogBackend struct {
+	w *syslog.Writer
+}
+
+func (s *syslogBackend) send(priority int, msg string, fields map[string]string) {
+	keys := make([]string, 0, len(fields))
+	for key := range fields {
+		keys = append(keys, key)
+	}
+	sort.Strings(keys)
+	for _, key := range keys {
+		msg += fmt.Sprintf(" %s=%q", key, fields[key])
+	}
+
+	switch priority {
+	case priorityWarning:
+		s.w.Warning(msg)
+	case priorityDebug:
+		s.w.Debug(msg)
+	default:
+		s.w.Info(msg)
+	}
+}
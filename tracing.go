@@ -0,0 +1,244 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"bytes"
+	"crypto/rand"
+	"encoding/hex"
+	"encoding/json"
+	"fmt"
+	"log"
+	"net/http"
+	"os"
+	"strings"
+	"time"
+
+	"github.com/spf13/viper"
+)
+
+// OTLP span kinds and status codes
+const (
+	spanInternal = 1
+	spanServer   = 2
+
+	statusOk    = 1
+	statusError = 2
+)
+
+// A span covers one phase of handling a request (zone discovery, an update,
+// verification), for export to an OpenTelemetry collector.
+type span struct {
+	traceID  string
+	spanID   string
+	parentID string
+	name     string
+	kind     int
+	start    time.Time
+	end      time.Time
+	attrs    map[string]string
+	err      error
+
+	parent *span
+	root   *span
+	spans  []*span // the finished spans of the trace, kept in the root
+}
+
+// Set by the daemon and the API server if tracing.endpoint is configured.
+// Requests are handled one at a time, so the current span is global.
+var (
+	tracing     bool
+	currentSpan *span
+)
+
+func tracingEndpoint() string {
+	endpoint := viper.GetString("tracing.endpoint")
+	if len(endpoint) == 0 {
+		endpoint = os.Getenv("OTEL_EXPORTER_OTLP_ENDPOINT")
+	}
+	if len(endpoint) == 0 || strings.HasSuffix(endpoint, "/v1/traces") {
+		return endpoint
+	}
+	return strings.TrimSuffix(endpoint, "/") + "/v1/traces"
+}
+
+func randomID(n int) string {
+	id := make([]byte, n)
+	rand.Read(id)
+	return hex.EncodeToString(id)
+}
+
+// startSpan starts a span as a child of the current one, or as the root of
+// a new trace if there is no current span. It returns nil (which the span
+// methods accept) if tracing is off. attrs are pairs of keys and values.
+func startSpan(name string, attrs ...string) *span {
+	if !tracing {
+		return nil
+	}
+	s := &span{
+		spanID: randomID(8),
+		name:   name,
+		kind:   spanInternal,
+		start:  time.Now(),
+		attrs:  make(map[string]string),
+		parent: currentSpan,
+	}
+	if currentSpan != nil {
+		s.traceID = currentSpan.traceID
+		s.parentID = currentSpan.spanID
+		s.root = currentSpan.root
+	} else {
+		s.traceID = randomID(16)
+		s.root = s
+	}
+	for i := 0; i+1 < len(attrs); i += 2 {
+		s.attrs[attrs[i]] = attrs[i+1]
+	}
+	currentSpan = s
+	return s
+}
+
+// startRequestSpan starts the root span for handling a request, continuing
+// the trace given in its W3C traceparent header if there is one.
+func startRequestSpan(name, traceparent string, attrs ...string) *span {
+	currentSpan = nil
+	s := startSpan(name, attrs...)
+	if s == nil {
+		return nil
+	}
+	s.kind = spanServer
+
+	// version-traceid-parentid-flags
+	parts := strings.Split(traceparent, "-")
+	if len(parts) == 4 && len(parts[1]) == 32 && len(parts[2]) == 16 {
+		if _, err := hex.DecodeString(parts[1] + parts[2]); err == nil &&
+			strings.Trim(parts[1], "0") != "" {
+			s.traceID, s.parentID = parts[1], parts[2]
+		}
+	}
+	return s
+}
+
+func (s *span) set(key, value string) {
+	if s != nil {
+		s.attrs[key] = value
+	}
+}
+
+// finish ends the span with the result err. When the root span of a trace
+// ends, the trace is exported.
+func (s *span) finish(err error) {
+	if s == nil {
+		return
+	}
+	s.end = time.Now()
+	s.err = err
+	s.root.spans = append(s.root.spans, s)
+	currentSpan = s.parent
+	if s == s.root {
+		go exportSpans(s.spans)
+	}
+}
+
+type otlpValue struct {
+	StringValue string `json:"stringValue"`
+}
+
+type otlpAttribute struct {
+	Key   string    `json:"key"`
+	Value otlpValue `json:"value"`
+}
+
+type otlpStatus struct {
+	Code    int    `json:"code"`
+	Message string `json:"message,omitempty"`
+}
+
+type otlpSpan struct {
+	TraceID      string          `json:"traceId"`
+	SpanID       string          `json:"spanId"`
+	ParentSpanID string          `json:"parentSpanId,omitempty"`
+	Name         string          `json:"name"`
+	Kind         int             `json:"kind"`
+	Start        string          `json:"startTimeUnixNano"`
+	End          string          `json:"endTimeUnixNano"`
+	Attributes   []otlpAttribute `json:"attributes"`
+	Status       otlpStatus      `json:"status"`
+}
+
+func otlpAttributes(attrs map[string]string) []otlpAttribute {
+	res := []otlpAttribute{}
+	for key, value := range attrs {
+		res = append(res, otlpAttribute{key, otlpValue{value}})
+	}
+	return res
+}
+
+// exportSpans sends spans to the OTLP/HTTP endpoint, JSON encoded.
+func exportSpans(spans []*span) {
+	var out []otlpSpan
+	for _, s := range spans {
+		o := otlpSpan{
+			TraceID:      s.traceID,
+			SpanID:       s.spanID,
+			ParentSpanID: s.parentID,
+			Name:         s.name,
+			Kind:         s.kind,
+			Start:        fmt.Sprint(s.start.UnixNano()),
+			End:          fmt.Sprint(s.end.UnixNano()),
+			Attributes:   otlpAttributes(s.attrs),
+			Status:       otlpStatus{Code: statusOk},
+		}
+		if s.err != nil {
+			o.Status = otlpStatus{statusError, s.err.Error()}
+		}
+		out = append(out, o)
+	}
+
+	resource := map[string]string{"service.name": viper.GetString("tracing.service-name")}
+	if host, err := os.Hostname(); err == nil {
+		resource["host.name"] = host
+	}
+	data, _ := json.Marshal(map[string]interface{}{
+		"resourceSpans": []interface{}{map[string]interface{}{
+			"resource": map[string]interface{}{"attributes": otlpAttributes(resource)},
+			"scopeSpans": []interface{}{map[string]interface{}{
+				"scope": map[string]string{"name": "update-dns"},
+				"spans": out,
+			}},
+		}},
+	})
+
+	url := tracingEndpoint()
+	req, err := http.NewRequest(http.MethodPost, url, bytes.NewReader(data))
+	if err == nil {
+		req.Header.Set("Content-Type", "application/json")
+		for key, value := range viper.GetStringMapString("tracing.headers") {
+			req.Header.Set(key, value)
+		}
+		var resp *http.Response
+		client := &http.Client{Timeout: 10 * time.Second}
+		if resp, err = client.Do(req); err == nil {
+			resp.Body.Close()
+			if resp.StatusCode/100 != 2 {
+				err = fmt.Errorf("%s", resp.Status)
+			}
+		}
+	}
+	if err != nil {
+		log.Printf("Unable to export traces to %s: %s", url, err)
+	}
+}
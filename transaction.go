@@ -0,0 +1,205 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"fmt"
+	"log"
+	"strings"
+
+	"github.com/miekg/dns"
+	"github.com/spf13/viper"
+)
+
+// The argument separating records given together on the command line.
+const recordSeparator = "+"
+
+// The types whose values can be given together on the command line, each
+// making a record of the RRset. Not TXT, whose arguments have always been
+// the strings of a single record; its records are separated with
+// recordSeparator.
+var multiValueTypes = map[uint16]bool{
+	dns.TypeA:    true,
+	dns.TypeAAAA: true,
+	dns.TypeNS:   true,
+}
+
+// splitRecords splits the command line arguments into records at each
+// recordSeparator, and into a record per value (see splitValues).
+func splitRecords(args []string) []string {
+	var records []string
+	start := 0
+	for i, arg := range args {
+		if arg == recordSeparator {
+			records = append(records, splitValues(args[start:i])...)
+			start = i + 1
+		}
+	}
+	return append(records, splitValues(args[start:])...)
+}
+
+// splitValues returns the record given by args or, if its type is in
+// multiValueTypes and several arguments follow the type, a record for each
+// of them: "host A 10.0.0.5 10.0.0.6" is both addresses.
+func splitValues(args []string) []string {
+	for i := 1; i < len(args) && i <= 3; i++ {
+		rrtype, ok := dns.StringToType[strings.ToUpper(args[i])]
+		if !ok {
+			continue
+		}
+		values := args[i+1:]
+		if !multiValueTypes[rrtype] || len(values) == 0 {
+			break
+		}
+
+		prefix := strings.Join(args[:i+1], " ")
+		var records []string
+		for _, value := range values {
+			records = append(records, prefix+" "+value)
+		}
+		return records
+	}
+	return []string{strings.Join(args, " ")}
+}
+
+// sendTransaction sends updates as one unit. Each zone's operations are
+// applied atomically by the server; if an update for one zone fails, the
+// updates already applied to other zones are rolled back.
+func sendTransaction(updates []*zoneUpdate) error {
+	if len(updates) == 1 {
+		_, err := sendUpdates(updates, false)
+		return err
+	}
+
+	unitLock.Lock()
+	defer unitLock.Unlock()
+	applied, err := sendUpdates(updates, !viper.GetBool("dry-run"))
+	if err == nil {
+		return nil
+	}
+	if len(applied) == 0 {
+		return err
+	}
+	if rerr := rollback(applied); rerr != nil {
+		return &partialError{fmt.Errorf("%s; %s", err, rerr)}
+	}
+	return fmt.Errorf("%w (the other zones were rolled back)", err)
+}
+
+// addPrereqs adds each prerequisite given with --prereq-exists and
+// --prereq-absent to the update of the zone it is in.
+func addPrereqs(updates []*zoneUpdate) error {
+	prereqs, err := flagPrereqs(".")
+	if err != nil {
+		return err
+	}
+	byZone := make(map[*zoneUpdate][]operation)
+	for _, prereq := range prereqs {
+		var target *zoneUpdate
+		for _, u := range updates {
+			if dns.IsSubDomain(u.zone, prereq.rr.Header().Name) &&
+				(target == nil || dns.CountLabel(u.zone) > dns.CountLabel(target.zone)) {
+				target = u
+			}
+		}
+		if target == nil {
+			return fmt.Errorf("%s: not in any of the updated zones", prereq.rr.Header().Name)
+		}
+		byZone[target] = append(byZone[target], prereq)
+	}
+	for u, ops := range byZone {
+		u.ops = append(ops, u.ops...)
+	}
+	return nil
+}
+
+// updateRecords sends the records given together on the command line as
+// one transaction, one update message per zone.
+func updateRecords(records []string) int {
+	if len(viper.GetStringSlice("expect")) > 0 {
+		log.Print("--expect can only be used with a single record")
+		return 1
+	}
+
+	action := flagAction()
+	built, err := recordBuilder(action, records...).operations()
+	if err != nil {
+		log.Printf("Not sending update: %s", err)
+		return 1
+	}
+
+	var ops, rops, reversed []operation
+	for _, b := range built {
+		rr := b.rr
+
+		if viper.GetBool("follow-cname") && rr.Header().Rrtype != dns.TypeCNAME {
+			target, err := followCNAME(rr.Header().Name)
+			if err != nil {
+				log.Printf("Unable to follow CNAME: %s", err)
+				return exitCode(err)
+			}
+			rr = withName(rr, target)
+		}
+
+		op := operation{action, rr}
+		ops = append(ops, op)
+		if reverseEnabled(rr.Header().Name) {
+			reversed = append(reversed, op)
+			if rop := reverseOp(op); rop != nil {
+				rops = append(rops, *rop)
+			}
+		}
+	}
+	if err := ptrConflicts(reversed); err != nil {
+		log.Printf("Not sending update: %s", err)
+		return 1
+	}
+	rops = append(staleReverseOps(reversed), rops...)
+
+	updates := groupByZone(replaceAll(ops))
+	if err := addPrereqs(updates); err != nil {
+		log.Printf("Invalid prerequisite: %s", err)
+		return 1
+	}
+	for _, u := range updates {
+		if err := checkGuards(u.zone, u.ops, true); err != nil {
+			log.Printf("Not updating zone %s: %s", u.zone, err)
+			return 1
+		}
+	}
+	// The PTR records are sent after the forward transaction (or before it,
+	// with --reverse-required), as for a single record
+	reverse := groupByZone(rops)
+	if len(reverse) > 0 {
+		err = sendWithReverse(updates, reverse)
+	} else {
+		err = sendTransaction(updates)
+	}
+	if err != nil {
+		log.Printf("Unable to send update: %s", err)
+		return exitCode(err)
+	}
+	infof("Updated %d records in %d zones", len(records), len(updates))
+
+	exitcode := 0
+	for _, u := range append(updates, reverse...) {
+		if err := waitSecondaries(u.zone); err != nil {
+			log.Printf("Secondaries of %s haven't caught up: %s", u.zone, err)
+			exitcode = exitNetwork
+		}
+	}
+	return exitcode
+}
@@ -0,0 +1,102 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"reflect"
+	"strings"
+	"testing"
+
+	"github.com/miekg/dns"
+)
+
+func TestSplitValues(t *testing.T) {
+	tests := []struct {
+		args []string
+		want []string
+	}{
+		{[]string{"www", "A", "192.0.2.1"}, []string{"www A 192.0.2.1"}},
+		{[]string{"www", "A", "192.0.2.1", "192.0.2.2"},
+			[]string{"www A 192.0.2.1", "www A 192.0.2.2"}},
+		{[]string{"www", "300", "IN", "AAAA", "2001:db8::1", "2001:db8::2"},
+			[]string{"www 300 IN AAAA 2001:db8::1", "www 300 IN AAAA 2001:db8::2"}},
+		{[]string{"www", "TXT", "v=spf1", "include:_spf.example.net", "-all"},
+			[]string{"www TXT v=spf1 include:_spf.example.net -all"}},
+		{[]string{"www", "TXT", `"a b" "c"`}, []string{`www TXT "a b" "c"`}},
+		{[]string{"www", "MX", "10", "mail"}, []string{"www MX 10 mail"}},
+		{[]string{"www", "A"}, []string{"www A"}},
+		{[]string{"www"}, []string{"www"}},
+	}
+	for _, test := range tests {
+		if got := splitValues(test.args); !reflect.DeepEqual(got, test.want) {
+			t.Errorf("splitValues(%q) = %q, want %q", test.args, got, test.want)
+		}
+	}
+}
+
+// The arguments after TXT are the strings of one record, as they were
+// before several values could be given for the other types.
+func TestSplitValuesTXT(t *testing.T) {
+	records := splitValues([]string{"www.example.org.", "TXT", "v=spf1", "include:_spf.example.net", "-all"})
+	if len(records) != 1 {
+		t.Fatalf("got %d records, want 1: %q", len(records), records)
+	}
+	rr, err := dns.NewRR(records[0])
+	if err != nil {
+		t.Fatal(err)
+	}
+	want := []string{"v=spf1", "include:_spf.example.net", "-all"}
+	if txt := rr.(*dns.TXT).Txt; !reflect.DeepEqual(txt, want) {
+		t.Errorf("TXT strings = %q, want %q", txt, want)
+	}
+}
+
+func TestSplitRecords(t *testing.T) {
+	args := []string{"a", "A", "192.0.2.1", "+", "b", "AAAA", "2001:db8::1", "2001:db8::2", "+", "c", "MX", "10", "mail",
+		"+", "d", "TXT", "one", "+", "d", "TXT", "two"}
+	want := []string{"a A 192.0.2.1", "b AAAA 2001:db8::1", "b AAAA 2001:db8::2", "c MX 10 mail", "d TXT one", "d TXT two"}
+	if got := splitRecords(args); !reflect.DeepEqual(got, want) {
+		t.Errorf("splitRecords(%q) = %q, want %q", args, got, want)
+	}
+}
+
+// With --reverse-required, the PTR records are sent before the forward
+// transaction, and rolled back with it when a zone of it fails.
+func TestSendWithReverseRollback(t *testing.T) {
+	b := useMockBackend(t, map[string]interface{}{"reverse-required": true})
+	b.refuse["example.net."] = true
+	forward := []*zoneUpdate{
+		{zone: "example.org.", ops: []operation{{"append", mustRR(t, "www.example.org. 300 IN A 192.0.2.1")}}},
+		{zone: "example.net.", ops: []operation{{"append", mustRR(t, "www.example.net. 300 IN A 192.0.2.1")}}},
+	}
+	reverse := []*zoneUpdate{
+		{zone: "2.0.192.in-addr.arpa.", ops: []operation{{"replace", mustRR(t, "1.2.0.192.in-addr.arpa. 300 IN PTR www.example.org.")}}},
+	}
+
+	err := sendWithReverse(forward, reverse)
+	if err == nil || !strings.Contains(err.Error(), "rolled back") || Kind(err) == ErrPartial {
+		t.Fatalf("got error %v, want a rolled back update", err)
+	}
+
+	var zones []string
+	for _, m := range b.updates {
+		zones = append(zones, msgName(m))
+	}
+	want := []string{"2.0.192.in-addr.arpa.", "example.org.", "example.net.", "example.org.", "2.0.192.in-addr.arpa."}
+	if !reflect.DeepEqual(zones, want) {
+		t.Errorf("updates sent to %q, want %q", zones, want)
+	}
+}
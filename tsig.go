@@ -0,0 +1,102 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"fmt"
+	"strconv"
+	"strings"
+	"time"
+
+	"github.com/miekg/dns"
+)
+
+// The difference between the server's clock and ours in seconds, learnt
+// from a BADTIME error with tsig-adjust-time, which is added to the time in
+// the TSIG records we sign.
+var clockOffset int64
+
+// A tsigError is returned when the server rejects the TSIG signature of a
+// message.
+type tsigError struct {
+	code   uint16
+	detail string
+	skew   int64 // for BADTIME: the server's clock minus ours, in seconds
+}
+
+func (e *tsigError) Error() string {
+	return fmt.Sprintf("TSIG error %s: %s", dns.RcodeToString[int(e.code)], e.detail)
+}
+
+// serverTime returns the server's time from the TSIG record of a BADTIME
+// error, which is in the other data field (RFC 8945), or the signing time
+// if it isn't there.
+func serverTime(t *dns.TSIG) int64 {
+	if t.OtherLen == 6 {
+		if v, err := strconv.ParseInt(t.OtherData, 16, 64); err == nil {
+			return v
+		}
+	}
+	return int64(t.TimeSigned)
+}
+
+// checkTsig returns a tsigError describing what to fix if the TSIG record
+// of the answer r to m reports an error.
+func checkTsig(m, r *dns.Msg) error {
+	if r == nil {
+		return nil
+	}
+	t := r.IsTsig()
+	if t == nil || t.Error == dns.RcodeSuccess {
+		return nil
+	}
+
+	var keyName, algorithm string
+	if mt := m.IsTsig(); mt != nil {
+		keyName, algorithm = mt.Hdr.Name, mt.Algorithm
+	}
+
+	switch t.Error {
+	case dns.RcodeBadTime:
+		skew := serverTime(t) - time.Now().Unix()
+		return &tsigError{t.Error, fmt.Sprintf("the clocks of this host and the server differ by %s "+
+			"(more than the allowed %ds); fix the clock, or set tsig-adjust-time to use the server's time",
+			time.Duration(skew)*time.Second, t.Fudge), skew}
+	case dns.RcodeBadKey:
+		detail := fmt.Sprintf("the server doesn't know the key %s with algorithm %s; check tsig-name and tsig-algorithm",
+			strings.TrimSuffix(keyName, "."), strings.TrimSuffix(algorithm, "."))
+		if !strings.EqualFold(t.Hdr.Name, keyName) {
+			detail += fmt.Sprintf(" (the server answered for the key %s)", t.Hdr.Name)
+		}
+		return &tsigError{t.Error, detail, 0}
+	case dns.RcodeBadSig:
+		return &tsigError{t.Error, fmt.Sprintf("the signature was wrong; the tsig-secret of %s doesn't match the server's",
+			strings.TrimSuffix(keyName, ".")), 0}
+	case dns.RcodeBadAlg:
+		return &tsigError{t.Error, fmt.Sprintf("the server doesn't support the algorithm %s",
+			strings.TrimSuffix(algorithm, ".")), 0}
+	}
+	return &tsigError{t.Error, fmt.Sprintf("the server rejected the signature with key %s", keyName), 0}
+}
+
+// unsign removes the TSIG record from m, so it can be signed again.
+func unsign(m *dns.Msg) {
+	if len(m.Extra) > 0 {
+		if _, ok := m.Extra[len(m.Extra)-1].(*dns.TSIG); ok {
+			m.Extra = m.Extra[:len(m.Extra)-1]
+		}
+	}
+}
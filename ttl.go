@@ -0,0 +1,95 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"log"
+	"strconv"
+	"strings"
+
+	"github.com/miekg/dns"
+	"github.com/spf13/viper"
+)
+
+// updateTTL changes the TTL of the RRset of the given type at a name (or of
+// all RRsets at the name, without a type), keeping the records as they are.
+// The update only goes through if the records haven't changed since they
+// were looked up.
+func updateTTL(args []string) int {
+	if len(args) < 2 || len(args) > 3 {
+		log.Print("Usage: update-dns --update-ttl <name> <ttl> [type]")
+		return 1
+	}
+
+	name := expandName(args[0])
+	ttl, err := strconv.ParseUint(args[1], 10, 32)
+	if err != nil {
+		log.Printf("Invalid TTL: %s", args[1])
+		return 1
+	}
+
+	var rrs []dns.RR
+	if len(args) == 3 {
+		if _, err := parseType(args[2]); err != nil {
+			log.Print(err)
+			return 1
+		}
+		rrs, err = currentRRset(strings.ToLower(name) + "/" + strings.ToUpper(args[2]))
+	} else {
+		rrs, err = recordsAt(name)
+	}
+	if err != nil {
+		log.Printf("Unable to look up %s: %s", name, err)
+		return exitCode(err)
+	}
+
+	var ops []operation
+	same := true
+	for _, rr := range rrs {
+		if !managedRecord(rr) {
+			continue
+		}
+		same = same && rr.Header().Ttl == uint32(ttl)
+		ops = append(ops, operation{"yxrr", rr})
+
+		rr = dns.Copy(rr)
+		rr.Header().Ttl = uint32(ttl)
+		ops = append(ops, operation{"replace", rr})
+	}
+	if len(ops) == 0 {
+		log.Printf("No records found at %s", name)
+		return 1
+	}
+	// The prerequisites would make sendUpdate send it anyway
+	if same && viper.GetBool("skip-unchanged") {
+		infof("%s already has TTL %d, not sending update", name, ttl)
+		updatesSkipped++
+		return 0
+	}
+
+	zone := getZone(name)
+	if err := checkGuards(zone, ops, true); err != nil {
+		log.Printf("Not sending update: %s", err)
+		return 1
+	}
+	if err := update(zone, ops); err != nil {
+		log.Printf("Unable to send update: %s", err)
+		return exitCode(err)
+	}
+
+	infof("Update successful")
+	return 0
+}
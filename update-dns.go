@@ -15,21 +15,42 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
-package main
+package updatedns
 
 import (
+	"encoding/hex"
+	"errors"
 	"fmt"
+	"io"
 	"log"
+	"math/rand"
+	"net"
 	"os"
 	"strings"
+	"syscall"
 	"time"
 
 	"github.com/miekg/dns"
+	"github.com/spf13/cast"
 	"github.com/spf13/pflag"
 	"github.com/spf13/viper"
 )
 
-func readConfig() string {
+// The flags of the program, for generating shell completions
+var commandFlags *pflag.FlagSet
+
+// The command being run ("update" for record updates), for the metrics,
+// the audit log and asking to confirm risky changes; empty in the library
+var command string
+
+// Commands that don't need a configured server and key
+var offlineCommands = map[string]bool{
+	"completions": true,
+	"config":      true,
+	"keygen":      true,
+}
+
+func readConfig() []string {
 	flag := pflag.FlagSet{}
 
 	flag.BoolP("delete", "d", false, "Delete name")
@@ -38,142 +59,1396 @@ func readConfig() string {
 	flag.BoolP("add", "a", false, "Add name")
 	viper.BindPFlag("add", flag.Lookup("add"))
 
+	flag.BoolP("reverse", "r", false, "Also update PTR records for A and AAAA records")
+	viper.BindPFlag("reverse", flag.Lookup("reverse"))
+
 	flag.StringP("server", "s", "", "Server name")
 	viper.BindPFlag("server", flag.Lookup("server"))
 
+	flag.String("algorithm", "", "TSIG algorithm (default hmac-sha256)")
+	viper.BindPFlag("tsig-algorithm", flag.Lookup("algorithm"))
+
+	flag.Bool("write", false, "Write the generated key to the config file")
+	viper.BindPFlag("keygen.write", flag.Lookup("write"))
+
 	flag.StringP("zone", "z", "", "Zone to update (will be auto-detected if absent)")
 	viper.BindPFlag("zone", flag.Lookup("zone"))
 
-	viper.SetDefault("debug", false)
+	flag.String("name", "", "Only list names matching this glob")
+	viper.BindPFlag("name", flag.Lookup("name"))
+
+	flag.StringP("type", "t", "", "Only list records of this type")
+	viper.BindPFlag("type", flag.Lookup("type"))
+
+	flag.Bool("prune", false, "Delete records not in the desired state file")
+	viper.BindPFlag("prune", flag.Lookup("prune"))
+
+	flag.String("older-than", "", "Only prune names whose ownership record is older than this (e.g. 30d)")
+	viper.BindPFlag("prune.older-than", flag.Lookup("older-than"))
+
+	// Not --domain, which would read like the domain setting names are
+	// relative to
+	flag.String("acme-domain", "", "Domain to create an ACME challenge record for")
+	viper.BindPFlag("acme.domain", flag.Lookup("acme-domain"))
+
+	flag.String("token", "", "ACME challenge token")
+	viper.BindPFlag("acme.token", flag.Lookup("token"))
+
+	flag.Bool("cleanup", false, "Remove the ACME challenge record")
+	viper.BindPFlag("acme.cleanup", flag.Lookup("cleanup"))
+
+	flag.Bool("wait", false, "Wait until all authoritative servers serve the ACME challenge")
+	viper.BindPFlag("acme.wait", flag.Lookup("wait"))
+
+	flag.String("listen", "127.0.0.1:8053", "Address for the HTTP API server to listen on")
+	viper.BindPFlag("serve.listen", flag.Lookup("listen"))
+
+	flag.StringSlice("resolver", nil, "Also verify against this resolver (can be repeated)")
+	viper.BindPFlag("verify.resolvers", flag.Lookup("resolver"))
+
+	flag.Int("timeout", 0, "Give up after this many seconds (for wait: default 300)")
+	viper.BindPFlag("timeout", flag.Lookup("timeout"))
+
+	flag.Bool("all-servers", false, "Wait for all authoritative nameservers instead of the configured server")
+	viper.BindPFlag("all-servers", flag.Lookup("all-servers"))
+
+	flag.String("output-file", "", "File to write exported zone to (default stdout)")
+	viper.BindPFlag("output-file", flag.Lookup("output-file"))
+
+	flag.StringP("output", "o", "text", "Output format for records (text or json)")
+	viper.BindPFlag("output", flag.Lookup("output"))
+	flag.String("color", "auto", "Color the output: auto (on a terminal), always or never")
+	viper.BindPFlag("color", flag.Lookup("color"))
+
+	flag.StringSlice("prereq-exists", nil, "Only update if name[:type[:value]] exists (can be repeated)")
+	viper.BindPFlag("prereq-exists", flag.Lookup("prereq-exists"))
+
+	flag.StringSlice("prereq-absent", nil, "Only update if name[:type] doesn't exist (can be repeated)")
+	viper.BindPFlag("prereq-absent", flag.Lookup("prereq-absent"))
+
+	flag.Bool("rollback", false, "With --reverse, roll back the update if updating the PTR record fails")
+	viper.BindPFlag("rollback", flag.Lookup("rollback"))
+
+	flag.Bool("reverse-required", false, "With --reverse, update the PTR record first and only update the record if that succeeds")
+	viper.BindPFlag("reverse-required", flag.Lookup("reverse-required"))
+
+	flag.String("wait-secondaries", "", "After updating, wait (at most this long) for all nameservers to serve the new serial")
+	flag.Lookup("wait-secondaries").NoOptDefVal = "5m"
+	viper.BindPFlag("wait-secondaries", flag.Lookup("wait-secondaries"))
+
+	flag.String("zone-discovery", "soa", "How to find the zone of a name: soa, ns or static, or several tried in turn (e.g. static,soa)")
+	viper.BindPFlag("zone-discovery", flag.Lookup("zone-discovery"))
+
+	flag.Bool("find-primary", false, "If the server isn't authoritative for the zone, send the update to the primary server in its SOA record")
+	viper.BindPFlag("find-primary", flag.Lookup("find-primary"))
+
+	flag.Bool("punycode", false, "Show internationalized names in punycode instead of Unicode")
+	viper.BindPFlag("punycode", flag.Lookup("punycode"))
+
+	flag.Bool("replace-all", false, "Delete all other records at the name, of any type")
+	viper.BindPFlag("replace-all", flag.Lookup("replace-all"))
+
+	flag.Bool("check-target", false, "Check that the targets of CNAME, MX, SRV and NS records resolve before updating")
+	viper.BindPFlag("check-target", flag.Lookup("check-target"))
+
+	flag.Bool("tsig-adjust-time", false, "If the server reports that our clock is off, retry with the server's time")
+	viper.BindPFlag("tsig-adjust-time", flag.Lookup("tsig-adjust-time"))
+
+	flag.String("reverse-server", "", "Server for PTR record updates (default from the zones section or --server)")
+	viper.BindPFlag("reverse-server", flag.Lookup("reverse-server"))
+
+	flag.String("ptr-name", "", "Name for PTR records to point at (default the name of the record)")
+	viper.BindPFlag("ptr-name", flag.Lookup("ptr-name"))
+
+	flag.Int("ptr-prefix-len", 0, "Make IPv6 PTR records for the prefix of this length instead of the full address")
+	viper.BindPFlag("ptr-prefix-len", flag.Lookup("ptr-prefix-len"))
+
+	flag.Bool("reverse-only", false, "Only update the PTR record: <address> <name> [ttl]")
+	viper.BindPFlag("reverse-only", flag.Lookup("reverse-only"))
+
+	flag.Int("report-fd", 0, "Write a JSON summary of the changed records to this file descriptor")
+	viper.BindPFlag("report-fd", flag.Lookup("report-fd"))
+	flag.String("report-file", "", "Write a JSON summary of the changed records to this file")
+	viper.BindPFlag("report-file", flag.Lookup("report-file"))
+
+	flag.Bool("progress", true, "Show the progress of batches and zone transfers (--progress=false to turn off)")
+	viper.BindPFlag("progress", flag.Lookup("progress"))
+
+	flag.Bool("show-diff", true, "Log the changes made to the records (--show-diff=false to turn off)")
+	viper.BindPFlag("show-diff", flag.Lookup("show-diff"))
+
+	flag.Bool("verify", false, "After updating, check that the server serves the new records")
+	viper.BindPFlag("verify-update", flag.Lookup("verify"))
+
+	flag.Bool("follow-cname", false, "If the name is a CNAME, update the name it points to instead")
+	viper.BindPFlag("follow-cname", flag.Lookup("follow-cname"))
+
+	flag.Bool("update-ttl", false, "Change the TTL of existing records: <name> <ttl> [type]")
+	viper.BindPFlag("update-ttl", flag.Lookup("update-ttl"))
+
+	flag.StringSlice("expect", nil, "Only update if the current value of the record is this (can be repeated)")
+	viper.BindPFlag("expect", flag.Lookup("expect"))
+
+	flag.Bool("force", false, "Update even if it would leave a CNAME next to other records, or is risky")
+	viper.BindPFlag("force", flag.Lookup("force"))
+
+	flag.Bool("detailed-exitcode", false, "Exit with status 7 if no update was needed")
+	viper.BindPFlag("detailed-exitcode", flag.Lookup("detailed-exitcode"))
+
+	flag.BoolP("dry-run", "n", false, "Print the updates that would be sent instead of sending them")
+	viper.BindPFlag("dry-run", flag.Lookup("dry-run"))
+
+	flag.BoolP("quiet", "q", false, "Only log errors and warnings")
+	flag.CountP("verbose", "v", "Log the messages sent (-vv: and the answers)")
+	flag.String("log-level", "", "Log level: warn, info, debug or trace")
+	viper.BindPFlag("log-level", flag.Lookup("log-level"))
+	flag.Bool("debug-dns", false, "Log the details of the DNS exchanges (transport, sizes and timing)")
+	viper.BindPFlag("debug-dns", flag.Lookup("debug-dns"))
+	flag.String("dump-packets", "", "Log every DNS message sent and received in full (--dump-packets=hex: with hex dumps)")
+	flag.Lookup("dump-packets").NoOptDefVal = "parsed"
+	viper.BindPFlag("dump-packets", flag.Lookup("dump-packets"))
+	flag.String("log", "", "Where to log: stderr (the default), journald or syslog")
+	viper.BindPFlag("log", flag.Lookup("log"))
+
+	flag.StringP("config", "c", "", "Config file to use (default ~/.update-dns/update-dns.yaml)")
 
-	viper.SetConfigName("update-dns")
-	viper.AddConfigPath("$HOME/.update-dns")
-	if err := viper.ReadInConfig(); err != nil {
+	flag.StringP("profile", "p", "", "Use the settings of this profile from the config file")
+	viper.BindPFlag("profile", flag.Lookup("profile"))
+
+	setDefaults()
+
+	// Every setting can be given in the environment, e.g. tsig-secret as
+	// UPDATE_DNS_TSIG_SECRET and daemon.ttl as UPDATE_DNS_DAEMON_TTL
+	viper.SetEnvPrefix("UPDATE_DNS")
+	viper.SetEnvKeyReplacer(strings.NewReplacer("-", "_", ".", "_"))
+	viper.AutomaticEnv()
+	viper.BindEnv("tsig-name", "UPDATE_DNS_TSIG_NAME", "UPDATE_DNS_KEY_NAME")
+	viper.BindEnv("tsig-secret", "UPDATE_DNS_TSIG_SECRET", "UPDATE_DNS_KEY_SECRET")
+	viper.BindEnv("tsig-algorithm", "UPDATE_DNS_TSIG_ALGORITHM", "UPDATE_DNS_KEY_ALGORITHM")
+
+	flag.Parse(os.Args[1:])
+	commandFlags = &flag
+	for key, name := range map[string]string{"server": "server", "tsig-algorithm": "algorithm", "reverse": "reverse"} {
+		if flag.Changed(name) {
+			overridden[key] = true
+		}
+	}
+
+	if len(flag.Args()) == 0 || len(flag.Args()[0]) == 0 {
+		log.Panic("Missing record name")
+	}
+	offline := offlineCommands[flag.Args()[0]]
+
+	configFile, _ := flag.GetString("config")
+	if len(configFile) == 0 {
+		configFile = os.Getenv("UPDATE_DNS_CONFIG")
+	}
+	if err := loadConfig(configFile); err != nil && !offline {
 		log.Panicf("Fatal error reading config file: %s \n", err)
 	}
 
-	flag.Parse(os.Args[1:])
+	if profile := viper.GetString("profile"); len(profile) > 0 {
+		selectProfile(profile)
+	}
 
-	if len(viper.GetString("server")) == 0 {
-		log.Panic("Missing server name")
+	quiet, _ := flag.GetBool("quiet")
+	verbose, _ := flag.GetCount("verbose")
+	if err := setLogLevel(viper.GetString("log-level"), quiet, verbose); err != nil {
+		log.Panic(err)
+	}
+	if err := setLogBackend(viper.GetString("log")); err != nil {
+		log.Panic(err)
 	}
 
-	if len(viper.GetString("tsig-secret")) == 0 {
-		log.Panic("Missing tsig-secret")
+	if offline {
+		return flag.Args()
 	}
 
-	if len(viper.GetString("tsig-name")) == 0 {
-		log.Panic("Missing tsig-name")
+	checkConfig()
+
+	return flag.Args()
+}
+
+// setDefaults sets the defaults of the settings that aren't given as
+// flags.
+func setDefaults() {
+	viper.SetDefault("debug", false)
+	viper.SetDefault("tsig-algorithm", "hmac-sha256")
+	viper.SetDefault("daemon.interval", "30s")
+	viper.SetDefault("daemon.ttl", 300)
+	viper.SetDefault("ddns.ttl", 300)
+	viper.SetDefault("acme.ttl", 60)
+	viper.SetDefault("acme.wait-timeout", "5m")
+	viper.SetDefault("verify.timeout", "2m")
+	viper.SetDefault("journal", true)
+	viper.SetDefault("history", true)
+	viper.SetDefault("journal-size", 1000)
+	viper.SetDefault("skip-unchanged", true)
+	viper.SetDefault("guard.wildcard", true)
+	viper.SetDefault("guard.apex", true)
+	viper.SetDefault("guard.ns", true)
+	viper.SetDefault("retry.attempts", 3)
+	viper.SetDefault("retry.backoff", "1s")
+	viper.SetDefault("retry.max-backoff", "30s")
+	viper.SetDefault("ddns.ipv4-url", "https://api.ipify.org")
+	viper.SetDefault("ddns.ipv6-url", "https://api6.ipify.org")
+	viper.SetDefault("vault.approle-mount", "approle")
+	viper.SetDefault("vault.refresh", "5m")
+	viper.SetDefault("metrics.job", "update-dns")
+	viper.SetDefault("tracing.service-name", "update-dns")
+	viper.SetDefault("audit.max-size", 10)
+	viper.SetDefault("audit.keep", 5)
+	viper.SetDefault("transfer-threshold", 10)
+	viper.SetDefault("registry.prefix", "_update-dns.")
+	viper.SetDefault("registry.refresh", "24h")
+}
+
+// checkConfig reads the per-zone settings and checks that the settings
+// are usable, panicking if they aren't.
+func checkConfig() {
+	readZoneRoutes()
+	readReverseZones()
+
+	// With per-zone settings, the top-level ones are only a fallback
+	if len(zoneRoutes) == 0 {
+		if len(viper.GetString("server")) == 0 {
+			log.Panic("Missing server name")
+		}
+
+		if len(tsigSecret(".")) == 0 {
+			log.Panic("Missing tsig-secret (or another source of the secret)")
+		}
+
+		if len(tsigName(".")) == 0 {
+			log.Panic("Missing tsig-name (or tsig-key-file or tsig-vault-path)")
+		}
 	}
 
-	if len(flag.Args()) == 0 || len(flag.Args()[0]) == 0 {
-		log.Panic("Missing record name")
+	tsigAlgorithm(".") // validate the top-level setting
+	checkZoneDiscovery()
+
+	if viper.GetBool("add") && viper.GetBool("delete") {
+		log.Panic("Cannot both add and delete")
+	}
+
+	if viper.GetBool("replace-all") && (viper.GetBool("add") || viper.GetBool("delete")) {
+		log.Panic("--replace-all cannot be combined with --add or --delete")
+	}
+
+	switch viper.GetString("output") {
+	case "text", "json":
+	default:
+		log.Panicf("Unknown output format: %s", viper.GetString("output"))
+	}
+
+	switch viper.GetString("dump-packets") {
+	case "", "parsed", "hex":
+	default:
+		log.Panicf("Unknown --dump-packets setting: %s (use parsed or hex)", viper.GetString("dump-packets"))
 	}
 
-	return strings.Join(flag.Args(), " ")
+	switch viper.GetString("color") {
+	case "auto", "always", "never":
+	default:
+		log.Panicf("Unknown --color setting: %s (use auto, always or never)", viper.GetString("color"))
+	}
 }
 
-func getZone(name string) string {
-	if len(viper.GetString("zone")) > 0 {
-		return dns.Fqdn(viper.GetString("zone"))
+// Map of TSIG algorithm names (as written by BIND and nsupdate) to the
+// names used by the dns library.
+var tsigAlgorithms = map[string]string{
+	"hmac-md5":    dns.HmacMD5,
+	"hmac-sha1":   dns.HmacSHA1,
+	"hmac-sha224": dns.HmacSHA224,
+	"hmac-sha256": dns.HmacSHA256,
+	"hmac-sha384": dns.HmacSHA384,
+	"hmac-sha512": dns.HmacSHA512,
+}
+
+func lookupTsigAlgorithm(name string) (string, bool) {
+	name = strings.ToLower(name)
+	name = strings.TrimSuffix(name, ".")
+	name = strings.TrimSuffix(name, ".sig-alg.reg.int")
+
+	alg, ok := tsigAlgorithms[name]
+	return alg, ok
+}
+
+// tsigAlgorithm returns the TSIG algorithm to use for name. The algorithm
+// in a BIND key file or Vault secret is used unless another one is given on
+// the command line.
+func tsigAlgorithm(name string) string {
+	algorithm := setting(name, "tsig-algorithm")
+	if key := sourceKey(name); key != nil && len(key.algorithm) > 0 && !overridden["tsig-algorithm"] {
+		algorithm = key.algorithm
 	}
 
-	c := new(dns.Client)
-	m := new(dns.Msg)
-	m.SetEdns0(4096, true)
-	m.SetQuestion(dns.Fqdn(name), dns.TypeSOA)
+	if alg, ok := lookupTsigAlgorithm(algorithm); ok {
+		return alg
+	}
 
-	r, _, err := c.Exchange(m, viper.GetString("server"))
+	log.Panicf("Unknown tsig-algorithm: %s", algorithm)
+	return ""
+}
+
+// Per-zone server and key settings from the zones section of the config
+// file, keyed by the lowercased zone name.
+var zoneRoutes map[string]map[string]string
+
+// Settings given on the command line or with nsupdate commands, which
+// apply to all zones.
+var overridden = make(map[string]bool)
+
+func readZoneRoutes() {
+	zoneRoutes = make(map[string]map[string]string)
+	for zone, settings := range viper.GetStringMap("zones") {
+		route, err := cast.ToStringMapStringE(settings)
+		if err != nil {
+			log.Panicf("Invalid settings for zone %s: %s", zone, err)
+		}
+		if alg, ok := route["tsig-algorithm"]; ok {
+			if _, ok := lookupTsigAlgorithm(alg); !ok {
+				log.Panicf("Unknown tsig-algorithm for zone %s: %s", zone, alg)
+			}
+		}
+		zoneRoutes[strings.ToLower(dns.Fqdn(zone))] = route
+	}
+}
+
+func overrideSetting(key, value string) {
+	viper.Set(key, value)
+	overridden[key] = true
+}
+
+// setting returns the value of a server or key setting to use for name,
+// taken from the most specific zone containing name in the zones section
+// of the config file, or from the top-level setting if there is none. The
+// server is the primary found with --find-primary, if there is one.
+func setting(name, key string) string {
+	if key == "server" {
+		if server := primaryFor(name); len(server) > 0 {
+			return server
+		}
+		if server := viper.GetString("reverse-server"); len(server) > 0 && isReverseName(name) {
+			return server
+		}
+	}
+	if overridden[key] {
+		return viper.GetString(key)
+	}
+
+	name = strings.ToLower(dns.Fqdn(name))
+	for off, end := 0, false; !end; off, end = dns.NextLabel(name, off) {
+		if value, ok := zoneRoutes[name[off:]][key]; ok {
+			return value
+		}
+	}
+	return viper.GetString(key)
+}
+
+func serverFor(name string) string {
+	server := setting(name, "server")
+	if len(server) == 0 {
+		log.Panicf("No server configured for %s", name)
+	}
+	return server
+}
+
+// msgName returns the name a message is about, which selects the server
+// and key to send it with.
+func msgName(m *dns.Msg) string {
+	if len(m.Question) > 0 {
+		return m.Question[0].Name
+	}
+	return "."
+}
+
+// Zones discovered so far, so that updating many names in the same zone
+// only queries the server once.
+var zoneCache = make(map[string]string)
+
+// The strategy that found the zone of each name in zoneCache
+var zoneMethods = make(map[string]string)
+
+// getZone returns the zone containing name. This is the zone given with
+// --zone for names inside it, so no queries are needed; others are found
+// with findZone. It panics if the zone can't be found.
+func getZone(name string) string {
+	name = strings.ToLower(dns.Fqdn(name))
+	if zone := dns.Fqdn(viper.GetString("zone")); len(viper.GetString("zone")) > 0 && dns.IsSubDomain(zone, name) {
+		findPrimary(zone)
+		zoneMethods[name] = "zone"
+		return zone
+	}
+
+	if zone, ok := zoneCache[name]; ok {
+		return zone
+	}
+	span := startSpan("discover zone", "dns.name", name)
+	zone, method, err := findZone(name)
 	if err != nil {
-		log.Panicf("Unable to discover zone: %s", err)
+		err = kindError(ErrResolution, err)
+		span.finish(err)
+		log.Print(err)
+		panic(err)
 	}
+	zoneCache[name] = zone
+	zoneMethods[name] = method
+	emit(Event{Type: EventZoneFound, Zone: zone, Name: name})
+	span.set("dns.zone", zone)
+	span.set("update_dns.zone_discovery", method)
+	span.finish(nil)
+	return zone
+}
 
-	for _, k := range r.Answer {
-		if soa, ok := k.(*dns.SOA); ok {
-			return soa.Hdr.Name
+// soaRecord returns a SOA record in the answer or authority section of r,
+// if its owner is name or one of its parents.
+func soaRecord(r *dns.Msg, name string) *dns.SOA {
+	for _, k := range append(r.Answer, r.Ns...) {
+		if soa, ok := k.(*dns.SOA); ok && dns.IsSubDomain(soa.Hdr.Name, name) {
+			return soa
 		}
 	}
+	return nil
+}
 
-	for _, k := range r.Ns {
-		if soa, ok := k.(*dns.SOA); ok {
-			return soa.Hdr.Name
+// discoverZone finds the zone containing name from the SOA record the
+// server returns for it: in the answer for the zone apex, and in the
+// authority section for other names. If there is none (e.g. an NXDOMAIN
+// without a SOA record, or a refused query), the parents of name are tried
+// in turn. With --find-primary, a non-authoritative answer or a referral
+// makes the updates of the zone go to its primary server.
+func discoverZone(name string) (string, error) {
+	c := new(dns.Client)
+	server := serverFor(name)
+
+	for off, end := 0, false; !end; off, end = dns.NextLabel(name, off) {
+		m := new(dns.Msg)
+		m.SetEdns0(4096, true)
+		m.SetQuestion(name[off:], dns.TypeSOA)
+
+		r, err := clientExchange(c, m, server)
+		if err != nil {
+			return "", fmt.Errorf("Unable to discover zone: %w", err)
+		}
+
+		if soa := soaRecord(r, name); soa != nil {
+			zone := strings.ToLower(soa.Hdr.Name)
+			if !r.Authoritative && viper.GetBool("find-primary") {
+				usePrimary(zone, soa.Ns, server)
+			}
+			return zone, nil
+		}
+
+		// A referral means the name is in a zone on other servers
+		if r.Rcode == dns.RcodeSuccess && !r.Authoritative {
+			for _, k := range r.Ns {
+				ns, ok := k.(*dns.NS)
+				if !ok {
+					continue
+				}
+				if !viper.GetBool("find-primary") {
+					return "", fmt.Errorf("%s is in %s, which is delegated to other servers than %s (use --find-primary to update its primary)",
+						name, ns.Hdr.Name, server)
+				}
+				zone, mname, err := delegatedPrimary(r, server)
+				if err != nil {
+					return "", fmt.Errorf("Unable to find the primary server of %s: %w", ns.Hdr.Name, err)
+				}
+				usePrimary(zone, mname, server)
+				return zone, nil
+			}
 		}
 	}
 
-	log.Panic("Couldn't find a zone")
-	return ""
+	return "", fmt.Errorf("Couldn't find a zone")
 }
 
-func update(rr dns.RR, zone string) error {
+func newClient(name string) *dns.Client {
 	c := new(dns.Client)
 	c.TsigSecret = make(map[string]string)
-	c.TsigSecret[viper.GetString("tsig-name")] = viper.GetString("tsig-secret")
+	c.TsigSecret[tsigName(name)] = tsigSecret(name)
+	return c
+}
+
+// sign adds a TSIG record to m unless it already has one. The dns library
+// removes the record from the message when signing it, so this has to be
+// done before each transmission.
+func sign(m *dns.Msg) {
+	if name := msgName(m); m.IsTsig() == nil {
+		m.SetTsig(tsigName(name), tsigAlgorithm(name), 300, time.Now().Unix()+clockOffset)
+	}
+}
+
+// A connCache keeps a TCP connection open so it can be reused for all
+// messages sent in a session.
+type connCache struct {
+	server string
+	conn   *dns.Conn
+}
+
+// When set, exchange sends all messages over the cached connection.
+var sharedConn *connCache
+
+func (cc *connCache) dial(c *dns.Client, server string) error {
+	cc.close()
+	dnsDebugf("Connecting to %s over TCP", server)
+	conn, err := c.Dial(server)
+	if err != nil {
+		dnsDebugf("Connecting to %s failed: %s", server, err)
+		return err
+	}
+	cc.server = server
+	cc.conn = conn
+	return nil
+}
+
+func (cc *connCache) close() {
+	if cc.conn != nil {
+		cc.conn.Close()
+		cc.conn = nil
+	}
+}
+
+func (cc *connCache) exchange(c *dns.Client, m *dns.Msg) (*dns.Msg, error) {
+	c.Net = "tcp"
+	server := serverFor(msgName(m))
+	if cc.conn == nil || cc.server != server {
+		if err := cc.dial(c, server); err != nil {
+			return nil, err
+		}
+	}
+
+	sign(m)
+	dnsDebugf("Sending %s %d (%d bytes) to %s over the open connection",
+		dns.OpcodeToString[m.Opcode], m.Id, m.Len(), server)
+	dumpPacket("Sent to", server, c, m)
+	r, rtt, err := c.ExchangeWithConn(m, cc.conn)
+	if err != nil {
+		// The server may have closed the connection while it was idle
+		dnsDebugf("Exchange with %s failed (%s), reconnecting", server, err)
+		if err := cc.dial(c, server); err != nil {
+			return nil, err
+		}
+		sign(m)
+		r, rtt, err = c.ExchangeWithConn(m, cc.conn)
+	}
+	if err == nil {
+		dnsDebugf("Received %s %d (%d bytes) from %s in %s", dns.RcodeToString[r.Rcode],
+			r.Id, r.Len(), server, rtt)
+		dumpPacket("Received from", server, nil, r)
+	}
+	return r, err
+}
+
+// retryable reports whether an exchange failed in a way that may well work
+// when tried again.
+func retryable(r *dns.Msg, err error) bool {
+	if err != nil {
+		var nerr net.Error
+		return errors.As(err, &nerr) || errors.Is(err, syscall.ECONNREFUSED) ||
+			errors.Is(err, syscall.ECONNRESET) || errors.Is(err, io.EOF)
+	}
+	return r.Rcode == dns.RcodeServerFailure
+}
+
+// exchange sends a message with exchangeOnce, retrying transient failures
+// (network errors, timeouts and SERVFAIL answers) up to retry.attempts
+// times in total, with exponential backoff and jitter between attempts.
+// TSIG errors are reported with a diagnosis; with tsig-adjust-time, a
+// BADTIME error is retried once with the server's time.
+func exchange(m *dns.Msg) (*dns.Msg, error) {
+	backoff := viper.GetDuration("retry.backoff")
+	adjusted := false
+	for attempt := 1; ; attempt++ {
+		r, err := exchangeOnce(m)
+		if terr := checkTsig(m, r); terr != nil {
+			var tsigErr *tsigError
+			if errors.As(terr, &tsigErr) && tsigErr.code == dns.RcodeBadTime &&
+				viper.GetBool("tsig-adjust-time") && !adjusted {
+				log.Printf("%s; retrying with the server's time", terr)
+				clockOffset += tsigErr.skew
+				adjusted = true
+				unsign(m)
+				continue
+			}
+			return r, terr
+		}
+		if attempt >= viper.GetInt("retry.attempts") || !retryable(r, err) {
+			return r, err
+		}
+
+		delay := backoff/2 + time.Duration(rand.Int63n(int64(backoff)+1))/2
+		if err == nil {
+			err = &rcodeError{r.Rcode}
+		}
+		log.Printf("Attempt %d failed (%s), retrying in %s", attempt, err, delay.Round(time.Millisecond))
+		if sleep(delay) != nil {
+			return r, err
+		}
+
+		if backoff *= 2; backoff > viper.GetDuration("retry.max-backoff") {
+			backoff = viper.GetDuration("retry.max-backoff")
+		}
+	}
+}
+
+// exchangeOnce sends a message to the configured server, signed with the
+// configured TSIG key (for the zone the message is about). Messages too
+// large for UDP are sent over TCP, and truncated responses are retried over
+// TCP.
+func exchangeOnce(m *dns.Msg) (*dns.Msg, error) {
+	c := newClient(msgName(m))
+	if sharedConn != nil && dnsBackend == nil {
+		return sharedConn.exchange(c, m)
+	}
+	server := serverFor(msgName(m))
+
+	// BIND's minimal-any leaves RRsets out of answers to ANY queries, but
+	// only over UDP
+	if m.Len() > dns.MinMsgSize || (len(m.Question) > 0 && m.Question[0].Qtype == dns.TypeANY) {
+		c.Net = "tcp"
+	}
+
+	sign(m)
+	r, err := clientExchange(c, m, server)
+	if err == nil && r.Truncated && c.Net != "tcp" {
+		dnsDebugf("Answer from %s truncated, retrying over TCP", server)
+		c.Net = "tcp"
+		sign(m)
+		r, err = clientExchange(c, m, server)
+	}
+	if r != nil {
+		tracef("Answer from %s:\n%s", server, r)
+	}
+	return r, err
+}
+
+// clientExchange sends m to server with c (or the Backend set with
+// Updater.SetBackend), logging the exchange with --debug-dns.
+func clientExchange(c *dns.Client, m *dns.Msg, server string) (*dns.Msg, error) {
+	network := c.Net
+	if len(network) == 0 {
+		network = "udp"
+	}
+	if dnsBackend != nil {
+		network = "the backend"
+	}
+	dnsDebugf("Sending %s %d (%d bytes) to %s over %s", dns.OpcodeToString[m.Opcode],
+		m.Id, m.Len(), server, network)
+	dumpPacket("Sent to", server, c, m)
+
+	var r *dns.Msg
+	var rtt time.Duration
+	var err error
+	if dnsBackend != nil {
+		start := time.Now()
+		r, err = dnsBackend.Exchange(m, server)
+		rtt = time.Since(start)
+	} else {
+		r, rtt, err = c.Exchange(m, server)
+	}
+	if err != nil {
+		dnsDebugf("Exchange with %s failed after %s: %s", server, rtt, err)
+	} else {
+		dnsDebugf("Received %s %d (%d bytes) from %s in %s", dns.RcodeToString[r.Rcode],
+			r.Id, r.Len(), server, rtt)
+		dumpPacket("Received from", server, nil, r)
+	}
+	return r, err
+}
+
+// dumpPacket logs m in full with --dump-packets, followed by a hex dump of
+// its wire format with --dump-packets=hex. Messages to be sent are signed
+// with the key in c for the dump as they will be when sent; the answers
+// received are packed again, so their name compression may differ from
+// what the server sent.
+func dumpPacket(what, server string, c *dns.Client, m *dns.Msg) {
+	mode := viper.GetString("dump-packets")
+	if len(mode) == 0 {
+		return
+	}
+	output(priorityDebug, "%s %s:\n%s", what, server, m)
+	if mode != "hex" {
+		return
+	}
+
+	// Signing removes the TSIG record from the message, so sign a copy
+	var wire []byte
+	var err error
+	if t := m.IsTsig(); c != nil && t != nil && len(c.TsigSecret[t.Hdr.Name]) > 0 {
+		wire, _, err = dns.TsigGenerate(m.Copy(), c.TsigSecret[t.Hdr.Name], "", false)
+	} else {
+		wire, err = m.Pack()
+	}
+	if err != nil {
+		output(priorityDebug, "Unable to pack message: %s", err)
+		return
+	}
+	output(priorityDebug, "%d bytes:\n%s", len(wire), hex.Dump(wire))
+}
+
+// An operation is a single change to be made to a zone. The action is one
+// of "replace", "append", "delete" (the RRset) or "remove" (the single
+// record), or one of the RFC 2136 prerequisites "yxdomain", "nxdomain",
+// "yxrrset", "nxrrset" and "yxrr" (the record exists with this value).
+type operation struct {
+	action string
+	rr     dns.RR
+}
+
+func rrsetKey(rr dns.RR) string {
+	return strings.ToLower(rr.Header().Name) + "/" + dns.TypeToString[rr.Header().Rrtype]
+}
+
+// buildUpdate creates an UPDATE message for zone containing all of ops.
+// Several replace operations for the same RRset only remove the existing
+// RRset once, so they add up to a new RRset containing all the records.
+func buildUpdate(zone string, ops []operation) *dns.Msg {
 	m := new(dns.Msg)
 	m.SetUpdate(zone)
 
-	if !viper.GetBool("add") {
-		m.RemoveRRset([]dns.RR{rr})
+	removed := make(map[string]bool)
+	for _, op := range ops {
+		// The dns library modifies the records it adds to the message
+		rrs := []dns.RR{dns.Copy(op.rr)}
+		switch op.action {
+		case "replace":
+			if !removed[rrsetKey(op.rr)] {
+				m.RemoveRRset(rrs)
+				removed[rrsetKey(op.rr)] = true
+			}
+			m.Insert(rrs)
+		case "append":
+			m.Insert(rrs)
+		case "delete":
+			m.RemoveRRset(rrs)
+		case "remove":
+			m.Remove(rrs)
+		case "yxdomain":
+			m.NameUsed(rrs)
+		case "nxdomain":
+			m.NameNotUsed(rrs)
+		case "yxrrset":
+			m.RRsetUsed(rrs)
+		case "nxrrset":
+			m.RRsetNotUsed(rrs)
+		case "yxrr":
+			m.Used(rrs)
+		}
+	}
+	return m
+}
+
+// unchanged reports whether applying ops to the records in before leaves
+// them as they are.
+func unchanged(before []dns.RR, ops []operation) bool {
+	after := applyToRecords(before, ops)
+	if len(after) != len(before) {
+		return false
+	}
+	for _, rr := range after {
+		if old := findRecord(before, rr); old == nil || old.Header().Ttl != rr.Header().Ttl {
+			return false
+		}
+	}
+	return true
+}
+
+// hasPrereqs reports whether ops includes prerequisites.
+func hasPrereqs(ops []operation) bool {
+	for _, op := range ops {
+		switch op.action {
+		case "yxdomain", "nxdomain", "yxrrset", "nxrrset", "yxrr":
+			return true
+		}
 	}
+	return false
+}
 
-	if !viper.GetBool("delete") {
-		m.Insert([]dns.RR{rr})
+// verifyApplied queries the server for the records changed by ops, to
+// check that it has actually applied them.
+func verifyApplied(ops []operation) error {
+	// Deletions are only checked if no records are added back later
+	check := make([]bool, len(ops))
+	readded := make(map[string]bool)
+	for i := len(ops) - 1; i >= 0; i-- {
+		name := strings.ToLower(ops[i].rr.Header().Name)
+		switch ops[i].action {
+		case "replace", "append":
+			check[i] = true
+			readded[rrsetKey(ops[i].rr)], readded[name] = true, true
+		case "delete", "remove":
+			check[i] = !readded[rrsetKey(ops[i].rr)] &&
+				!(ops[i].rr.Header().Rrtype == dns.TypeANY && readded[name])
+		}
 	}
 
-	m.SetTsig(viper.GetString("tsig-name"), dns.HmacSHA256, 300, time.Now().Unix())
+	for i, op := range ops {
+		if !check[i] {
+			continue
+		}
+
+		rrs, err := currentRRset(rrsetKey(op.rr))
+		if err != nil {
+			return fmt.Errorf("unable to verify update: %w", err)
+		}
+
+		switch op.action {
+		case "replace", "append":
+			if findRecord(rrs, op.rr) == nil {
+				return fmt.Errorf("server accepted the update, but doesn't serve %s", op.rr)
+			}
+		case "delete":
+			if len(rrs) > 0 {
+				return fmt.Errorf("server accepted the update, but still serves %s", rrs[0])
+			}
+		case "remove":
+			if findRecord(rrs, op.rr) != nil {
+				return fmt.Errorf("server accepted the update, but still serves %s", op.rr)
+			}
+		}
+	}
+	infof("Verified that the server serves the update")
+	return nil
+}
 
-	log.Printf("Sending update:\n%s", m)
+func sendUpdate(zone string, ops []operation) (r *dns.Msg, err error) {
+	updateLock.Lock()
+	defer updateLock.Unlock()
 
-	r, _, err := c.Exchange(m, viper.GetString("server"))
+	var state *journalEntry
+	status, start := "failed", time.Now()
+	span := startSpan("update", "dns.zone", zone, "dns.server", setting(zone, "server"))
+	defer func() {
+		addResult(zone, ops, state, status, r, err, time.Since(start))
+		auditUpdate(zone, ops, state, status, r, err)
+		emitUpdate(zone, ops, state, status, err)
+		span.set("dns.result", status)
+		if r != nil {
+			span.set("dns.rcode", dns.RcodeToString[r.Rcode])
+		}
+		span.finish(err)
+	}()
+
+	if err := cancelled(); err != nil && !rollingBack {
+		return nil, err
+	}
+
+	// The commands check the guards first, so they can refuse a whole
+	// transaction before sending any of it; this covers all the others.
+	// Only the command line tool asks for confirmation.
+	if err := checkGuards(zone, ops, len(command) > 0); err != nil {
+		return nil, kindError(ErrValidation, err)
+	}
+
+	if registryEnabled() {
+		ops = addOwnership(ops)
+	}
+	m := buildUpdate(zone, ops)
+	if !viper.GetBool("force") {
+		if err := cnameConflicts(ops); err != nil {
+			return nil, err
+		}
+	}
+	if viper.GetBool("check-target") {
+		if err := checkTargets(ops); err != nil {
+			return nil, err
+		}
+	}
+	entry := journalState(zone, ops)
+	state = entry
+	if state == nil && (viper.GetBool("skip-unchanged") || viper.GetBool("show-diff") || jsonResults() || auditEnabled()) {
+		state, _ = captureState(zone, ops)
+	}
+	if state != nil && registryEnabled() {
+		keepOwnership(ops, state.before)
+		m = buildUpdate(zone, ops)
+	}
+
+	// Don't bump the serial with an update that changes nothing, unless it
+	// has prerequisites, which only the server can check (e.g. --expect)
+	if viper.GetBool("skip-unchanged") && state != nil && !hasPrereqs(ops) && unchanged(state.before, ops) {
+		infof("%s already up to date, not sending update", strings.Join(state.RRsets, ", "))
+		updatesSkipped++
+		status = "unchanged"
+		r = new(dns.Msg)
+		r.SetReply(m)
+		return r, nil
+	}
+
+	if viper.GetBool("dry-run") {
+		if state != nil && viper.GetBool("show-diff") {
+			logChanges(state, ops)
+		}
+		if !jsonResults() {
+			printNsupdate(os.Stdout, setting(zone, "server"), zone, ops)
+		}
+		status = "dry-run"
+		r = new(dns.Msg)
+		r.SetReply(m)
+		return r, nil
+	}
+
+	infof("Sending update for %s to %s", zone, setting(zone, "server"))
+	emit(Event{Type: EventUpdateStarted, Zone: zone})
+	debugf("Update message:\n%s", m)
+
+	r, err = exchange(m)
+	if err == nil && r.Rcode != dns.RcodeSuccess {
+		err = &rcodeError{r.Rcode}
+	}
+	updatesSent++
+	if err == nil && viper.GetBool("verify-update") {
+		verify := startSpan("verify", "dns.zone", zone)
+		err = verifyApplied(ops)
+		verify.finish(err)
+	}
+	if err == nil {
+		status = "sent"
+		if state != nil && viper.GetBool("show-diff") {
+			logChanges(state, ops)
+		}
+	}
+	entry.record(ops, err)
+	return r, err
+}
+
+func update(zone string, ops []operation) error {
+	_, err := sendUpdate(zone, ops)
+	return err
+}
+
+// expandName makes name absolute. Names without a trailing dot are
+// relative to the domain setting if it is set. Internationalized names are
+// converted to punycode; invalid ones are left for the parser to reject.
+func expandName(name string) string {
+	if ascii, err := toASCII(name); err == nil {
+		name = ascii
+	}
+	domain, err := toASCII(viper.GetString("domain"))
 	if err != nil {
-		return err
-	} else if r.Rcode != dns.RcodeSuccess {
-		return fmt.Errorf("Server refused registration. Code: %s",
-			dns.RcodeToString[r.Rcode])
+		log.Panicf("Invalid domain setting: %s", err)
+	}
+	if len(domain) == 0 || dns.IsFqdn(name) {
+		return dns.Fqdn(name)
+	} else if name == "@" {
+		return dns.Fqdn(domain)
+	}
+	return name + "." + dns.Fqdn(domain)
+}
+
+// recordFields returns the fields of the record in record, skipping any
+// $TTL or $ORIGIN lines before it.
+func recordFields(record string) []string {
+	for _, line := range strings.Split(record, "\n") {
+		if fields := strings.Fields(line); len(fields) > 0 && !strings.HasPrefix(fields[0], "$") {
+			return fields
+		}
 	}
 	return nil
 }
 
-func main() {
+// hasClass reports whether a class is given in the fields after the name of
+// a record, where it may come before or after the TTL.
+func hasClass(fields []string) bool {
+	for i := 0; i < 2 && i < len(fields); i++ {
+		if _, ok := dns.StringToClass[strings.ToUpper(fields[i])]; ok {
+			return true
+		}
+	}
+	return false
+}
 
-	exitcode := 0
+// recordDefaults returns the directives setting the default TTL and origin
+// for a record at name.
+func recordDefaults(name string) string {
+	var prefix string
+	if ttl := setting(name, "ttl"); len(ttl) > 0 {
+		prefix += "$TTL " + ttl + "\n"
+	}
+	if domain := viper.GetString("domain"); len(domain) > 0 {
+		prefix += "$ORIGIN " + expandName("@") + "\n"
+	}
+	return prefix
+}
 
-	defer func() {
-		if !viper.GetBool("debug") {
-			r := recover() // suppress stack traces
-			if r != nil {
-				exitcode = 2
+// reverseEnabled reports whether PTR records should be updated along with
+// the addresses at name.
+func reverseEnabled(name string) bool {
+	return cast.ToBool(setting(name, "reverse"))
+}
+
+// parseRecord parses a record given in zone file format, using the default
+// TTL, class and domain from the config. When deleting, the record can be
+// just a name, which deletes all records at that name. Internationalized
+// names are converted to punycode.
+func parseRecord(record string, action string) (dns.RR, error) {
+	record, err := toASCII(record)
+	if err != nil {
+		return nil, err
+	}
+	fields := recordFields(record)
+	if len(fields) == 0 {
+		return nil, fmt.Errorf("empty record")
+	}
+	name := expandName(fields[0])
+
+	rr, err := dns.NewRR(recordDefaults(name) + record)
+	if err == nil && rr != nil {
+		if class := setting(name, "class"); len(class) > 0 && !hasClass(fields[1:]) {
+			c, ok := dns.StringToClass[strings.ToUpper(class)]
+			if !ok {
+				return nil, fmt.Errorf("unknown class: %s", class)
 			}
+			rr.Header().Class = c
 		}
-		os.Exit(exitcode)
-	}()
+		normalizeRecord(rr, fields)
+		return rr, nil
+	}
+
+	if action == "delete" && len(fields) == 1 {
+		return &dns.ANY{Hdr: dns.RR_Header{Name: name,
+			Ttl:    0,
+			Rrtype: dns.TypeANY,
+			Class:  dns.ClassANY}}, nil
+	}
+
+	if err == nil {
+		err = fmt.Errorf("empty record")
+	}
+	return nil, err
+}
+
+// recordTargets returns the domain names in the data of rr.
+func recordTargets(rr dns.RR) []*string {
+	switch rr := rr.(type) {
+	case *dns.CNAME:
+		return []*string{&rr.Target}
+	case *dns.DNAME:
+		return []*string{&rr.Target}
+	case *dns.MX:
+		return []*string{&rr.Mx}
+	case *dns.SRV:
+		return []*string{&rr.Target}
+	case *dns.NS:
+		return []*string{&rr.Ns}
+	case *dns.PTR:
+		return []*string{&rr.Ptr}
+	}
+	return nil
+}
 
-	record := readConfig()
-	name := strings.SplitN(record, " ", 2)[0]
-	zone := getZone(name)
+// normalizeRecord lowercases the name and the targets of rr, so they
+// compare equal to the records on the server. It warns about targets
+// given without a trailing dot that look like they were meant to be fully
+// qualified, but were made relative to the domain setting, and about
+// single-label targets, which are rarely intended.
+func normalizeRecord(rr dns.RR, fields []string) {
+	rr.Header().Name = strings.ToLower(rr.Header().Name)
 
-	log.Printf("Got zone: %s", zone)
+	origin := "."
+	if len(viper.GetString("domain")) > 0 {
+		origin = expandName("@")
+	}
+	for _, target := range recordTargets(rr) {
+		*target = strings.ToLower(*target)
+		for _, field := range fields[1:] {
+			field = strings.ToLower(field)
+			if strings.HasSuffix(field, ".") {
+				continue
+			}
+			if origin == "." && field+"." == *target && !strings.Contains(field, ".") && field != "@" {
+				log.Printf("Warning: the target %s of %s is a single label; add the domain if it was meant to be fully qualified",
+					*target, rr.Header().Name)
+			} else if origin != "." && field+"."+origin == *target && strings.Contains(field, ".") {
+				log.Printf("Warning: the target %s has no trailing dot, so it became %s; add a dot if it was meant to be fully qualified",
+					field, *target)
+			}
+		}
+	}
+}
 
-	rr, err := dns.NewRR(record)
+func flagAction() string {
+	if viper.GetBool("delete") {
+		return "delete"
+	} else if viper.GetBool("add") {
+		return "append"
+	}
+	return "replace"
+}
+
+// replaceAll returns ops, preceded with --replace-all by the deletion of
+// all the existing RRsets at each name, so the names only have the given
+// records afterwards.
+func replaceAll(ops []operation) []operation {
+	if !viper.GetBool("replace-all") {
+		return ops
+	}
+
+	var res []operation
+	seen := make(map[string]bool)
+	for _, op := range ops {
+		name := strings.ToLower(op.rr.Header().Name)
+		if !seen[name] {
+			seen[name] = true
+			res = append(res, operation{"delete", &dns.ANY{Hdr: dns.RR_Header{Name: op.rr.Header().Name,
+				Rrtype: dns.TypeANY}}})
+		}
+	}
+	return append(res, ops...)
+}
+
+// parsePrereq parses a prerequisite given as name[:type[:value]], which is
+// required to exist or (without a value) to be absent.
+func parsePrereq(prereq string, exists bool) (operation, error) {
+	parts := strings.SplitN(prereq, ":", 3)
+	kinds := map[bool][]string{true: {"yxdomain", "yxrrset", "yxrr"}, false: {"nxdomain", "nxrrset"}}
+	if len(parts) > len(kinds[exists]) {
+		return operation{}, fmt.Errorf("%s: a value can only be given for records that must exist", prereq)
+	}
+
+	rr, err := parseRecord(strings.Join(parts, " "), "delete")
 	if err != nil {
-		if viper.GetBool("delete") {
-			rr = &dns.ANY{Hdr: dns.RR_Header{Name: dns.Fqdn(name),
-				Ttl:    0,
-				Rrtype: dns.TypeANY,
-				Class:  dns.ClassANY}}
-		} else {
-			log.Printf("Unable to parse record: %s", err)
-			exitcode = 1
-			return
+		return operation{}, fmt.Errorf("%s: %s", prereq, err)
+	}
+	return operation{kinds[exists][len(parts)-1], rr}, nil
+}
+
+// flagPrereqs returns the prerequisites given with --prereq-exists and
+// --prereq-absent, which must all be in zone.
+func flagPrereqs(zone string) ([]operation, error) {
+	var ops []operation
+	for _, exists := range []bool{true, false} {
+		key := "prereq-absent"
+		if exists {
+			key = "prereq-exists"
+		}
+		for _, prereq := range viper.GetStringSlice(key) {
+			op, err := parsePrereq(prereq, exists)
+			if err != nil {
+				return nil, err
+			}
+			if !dns.IsSubDomain(zone, op.rr.Header().Name) {
+				return nil, fmt.Errorf("%s: not in zone %s", prereq, zone)
+			}
+			ops = append(ops, op)
+		}
+	}
+	return ops, nil
+}
+
+// followCNAME returns the name at the end of the chain of CNAME records
+// starting at name, or name itself if it has no CNAME record.
+func followCNAME(name string) (string, error) {
+	seen := make(map[string]bool)
+	for !seen[strings.ToLower(name)] {
+		seen[strings.ToLower(name)] = true
+
+		rrs, err := lookup(name, dns.TypeCNAME)
+		if err != nil {
+			return "", err
 		}
+		var target string
+		for _, rr := range rrs {
+			if cname, ok := rr.(*dns.CNAME); ok && strings.EqualFold(cname.Hdr.Name, name) {
+				target = cname.Target
+			}
+		}
+		if len(target) == 0 {
+			return name, nil
+		}
+		infof("Following CNAME from %s to %s", name, target)
+		name = target
+	}
+	return "", fmt.Errorf("CNAME loop at %s", name)
+}
+
+// expectPrereqs returns the prerequisites that the RRset of rr currently
+// consists of exactly the records with the values given with --expect.
+func expectPrereqs(rr dns.RR) ([]operation, error) {
+	var ops []operation
+	for _, value := range viper.GetStringSlice("expect") {
+		if rr.Header().Rrtype == dns.TypeANY {
+			return nil, fmt.Errorf("--expect needs a record type")
+		}
+		expected, err := parseRecord(fmt.Sprintf("%s %s %s", rr.Header().Name,
+			dns.TypeToString[rr.Header().Rrtype], value), "replace")
+		if err != nil {
+			return nil, fmt.Errorf("invalid expected value %s: %s", value, err)
+		}
+		ops = append(ops, operation{"yxrr", expected})
+	}
+	return ops, nil
+}
+
+func updateRecord(record string) int {
+	action := flagAction()
+	built, err := recordBuilder(action, record).operations()
+	if err != nil {
+		log.Printf("Not sending update: %s", err)
+		return 1
 	}
+	rr := built[0].rr
 
-	err = update(rr, zone)
+	if viper.GetBool("follow-cname") && rr.Header().Rrtype != dns.TypeCNAME {
+		target, err := followCNAME(rr.Header().Name)
+		if err != nil {
+			log.Printf("Unable to follow CNAME: %s", err)
+			return exitCode(err)
+		}
+		rr = withName(rr, target)
+	}
+
+	zone := getZone(rr.Header().Name)
+	infof("Got zone: %s", zone)
+
+	prereqs, err := flagPrereqs(zone)
+	if err == nil {
+		var expect []operation
+		expect, err = expectPrereqs(rr)
+		prereqs = append(prereqs, expect...)
+	}
+	if err != nil {
+		log.Printf("Invalid prerequisite: %s", err)
+		return 1
+	}
+
+	ops := append(prereqs, replaceAll([]operation{{action, rr}})...)
+	if err := checkGuards(zone, ops, true); err != nil {
+		log.Printf("Not sending update: %s", err)
+		return 1
+	}
+	if reverseEnabled(rr.Header().Name) {
+		err = updateWithReverse(zone, ops)
+	} else {
+		err = update(zone, ops)
+	}
 	if err != nil {
 		log.Printf("Unable to send update: %s", err)
-		exitcode = 1
+		return exitCode(err)
+	}
+
+	infof("Update successful")
+
+	if err := waitSecondaries(zone); err != nil {
+		log.Printf("Secondaries haven't caught up: %s", err)
+		return exitNetwork
+	}
+	return 0
+}
+
+// Commands that --timeout doesn't bound as a whole: those that run until
+// they are stopped, and wait, for which it is the time to wait.
+var untimedCommands = map[string]bool{
+	"daemon": true,
+	"serve":  true,
+	"shell":  true,
+	"wait":   true,
+}
+
+// startTimeout makes the program exit with exitTimeout when the --timeout
+// expires, however far it has got, so a hung server can't block it
+// forever. Like a signal, it lets the update in progress finish (its
+// queries time out on their own, and its retries are cut short).
+func startTimeout(command string) {
+	timeout := time.Duration(viper.GetInt("timeout")) * time.Second
+	if timeout <= 0 || untimedCommands[command] {
 		return
 	}
+	time.AfterFunc(timeout, func() {
+		stop(exitTimeout, fmt.Sprintf("Timed out after %s", timeout))
+	})
+}
+
+// Main runs the update-dns command line tool with the arguments in os.Args,
+// and exits with its exit status.
+func Main() {
 
-	log.Print("Update successful")
+	exitcode := 0
 
+	defer func() {
+		if !viper.GetBool("debug") {
+			r := recover() // suppress stack traces
+			if err, ok := r.(error); ok {
+				exitcode = exitCode(err)
+			} else if r != nil {
+				exitcode = exitConfig
+			}
+		}
+		if exitcode == 0 && viper.GetBool("detailed-exitcode") && updatesSent == 0 && updatesSkipped > 0 {
+			exitcode = exitUnchanged
+		}
+		exit(exitcode)
+	}()
+
+	args := readConfig()
+	handleSignals()
+	startTimeout(args[0])
+	command = "update"
+	for _, name := range commandNames {
+		if args[0] == name {
+			command = name
+		}
+	}
+
+	switch args[0] {
+	case "query":
+		exitcode = query(args[1:])
+	case "list":
+		exitcode = list(args[1:])
+	case "batch":
+		exitcode = batch(args[1:])
+	case "nsupdate":
+		exitcode = nsupdate(args[1:])
+	case "shell":
+		exitcode = shell(args[1:])
+	case "rename":
+		exitcode = rename(args[1:])
+	case "copy":
+		exitcode = copyRecords(args[1:])
+	case "export":
+		exitcode = export(args[1:])
+	case "apply":
+		exitcode = apply(args[1:])
+	case "diff":
+		exitcode = diff(args[1:])
+	case "daemon":
+		exitcode = daemon(args[1:])
+	case "ddns":
+		exitcode = ddns(args[1:])
+	case "acme":
+		exitcode = acme(args[1:])
+	case "serve":
+		exitcode = serve(args[1:])
+	case "prune":
+		exitcode = prune(args[1:])
+	case "check":
+		exitcode = check(args[1:])
+	case "completions":
+		exitcode = completions(args[1:])
+	case "config":
+		exitcode = configCommand(args[1:])
+	case "keygen":
+		exitcode = keygen(args[1:])
+	case "verify":
+		exitcode = verify(args[1:])
+	case "undo":
+		exitcode = undo(args[1:])
+	case "history":
+		exitcode = history(args[1:])
+	case "wait":
+		exitcode = wait(args[1:])
+	default:
+		records := splitRecords(args)
+		if viper.GetBool("update-ttl") {
+			exitcode = updateTTL(args)
+		} else if viper.GetBool("reverse-only") {
+			exitcode = reverseOnly(args)
+		} else if len(records) > 1 {
+			exitcode = updateRecords(records)
+		} else {
+			exitcode = updateRecord(records[0])
+		}
+	}
 }
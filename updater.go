@@ -0,0 +1,270 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+// Package updatedns sends signed dynamic DNS updates (RFC 2136), finding
+// the zone to update by querying for its SOA record. It is the library
+// behind the update-dns command line tool, and an Updater does what the
+// tool does for a single record.
+//
+// All calls block until they are done; they don't need a goroutine of
+// their own. The settings are kept globally (in viper), as for the command
+// line tool, so a program should only use one Updater at a time; calls from
+// several goroutines are done one at a time.
+package updatedns
+
+import (
+	"fmt"
+	"strings"
+	"sync"
+
+	"github.com/miekg/dns"
+	"github.com/spf13/viper"
+)
+
+// An Updater sends updates with the settings it was created with.
+type Updater struct{}
+
+// Held during each call of the library API, as the settings and caches are
+// global.
+var apiLock sync.Mutex
+
+// NewUpdater returns an Updater using the settings in config, which has
+// the same keys as the config file, e.g.:
+//
+//	updatedns.NewUpdater(map[string]interface{}{
+//		"server":      "ns.example.org:53",
+//		"tsig-name":   "updclient.example.org.",
+//		"tsig-secret": secret,
+//	})
+//
+// Only errors and warnings are logged, unless config sets log-level.
+func NewUpdater(config map[string]interface{}) (*Updater, error) {
+	return newUpdater(nil, config)
+}
+
+// NewUpdaterFromFile returns an Updater using the settings in a config
+// file, or in the files the command line tool reads if file is empty, with
+// the settings in config (which may be nil) taking precedence. A profile
+// can be selected by setting profile in config.
+func NewUpdaterFromFile(file string, config map[string]interface{}) (*Updater, error) {
+	return newUpdater(func() error { return loadConfig(file) }, config)
+}
+
+func newUpdater(load func() error, config map[string]interface{}) (u *Updater, err error) {
+	apiLock.Lock()
+	defer apiLock.Unlock()
+	defer recoverError(&err)
+
+	setDefaults()
+	viper.SetDefault("output", "text")
+	viper.SetDefault("color", "never")
+	viper.SetDefault("log-level", "warn")
+	viper.SetDefault("zone-discovery", "soa")
+	if load != nil {
+		if err := load(); err != nil {
+			return nil, kindError(ErrConfig, err)
+		}
+	}
+	for key, value := range config {
+		viper.Set(key, value)
+	}
+	if profile := viper.GetString("profile"); len(profile) > 0 {
+		selectProfile(profile)
+	}
+
+	if err := setLogLevel(viper.GetString("log-level"), false, 0); err != nil {
+		return nil, err
+	}
+	if err := setLogBackend(viper.GetString("log")); err != nil {
+		return nil, err
+	}
+	checkConfig()
+	return &Updater{}, nil
+}
+
+// recoverError turns a panic (which is how invalid settings and failed zone
+// discovery are reported) into an error returned in err.
+func recoverError(err *error) {
+	if r := recover(); r != nil {
+		if e, ok := r.(error); ok {
+			*err = e
+		} else {
+			*err = kindError(ErrConfig, fmt.Errorf("%v", r))
+		}
+	}
+}
+
+// Zone returns the zone name belongs to, as discovered from the server.
+func (u *Updater) Zone(name string) (zone string, err error) {
+	apiLock.Lock()
+	defer apiLock.Unlock()
+	defer recoverError(&err)
+	return getZone(expandName(name)), nil
+}
+
+// Lookup returns the records of type rrtype (e.g. "A", or "ANY" for all
+// types) at name on the configured server.
+func (u *Updater) Lookup(name, rrtype string) (rrs []dns.RR, err error) {
+	apiLock.Lock()
+	defer apiLock.Unlock()
+	defer recoverError(&err)
+	qtype, err := parseType(rrtype)
+	if err != nil {
+		return nil, kindError(ErrValidation, err)
+	}
+	return lookup(expandName(name), qtype)
+}
+
+// send sends an update with the single operation action on record, also
+// updating the PTR record if reverse updates are enabled for its name.
+func (u *Updater) send(action, record string) (err error) {
+	apiLock.Lock()
+	defer apiLock.Unlock()
+	defer recoverError(&err)
+	rr, err := parseRecord(record, action)
+	if err != nil {
+		return kindError(ErrValidation, err)
+	}
+
+	zone := getZone(rr.Header().Name)
+	ops := []operation{{action, rr}}
+	if err := checkGuards(zone, ops, false); err != nil {
+		return kindError(ErrValidation, err)
+	}
+	if reverseEnabled(rr.Header().Name) {
+		return updateWithReverse(zone, ops)
+	}
+	return update(zone, ops)
+}
+
+// An Operation is one change of a batch: an action ("replace", "append",
+// "remove" or "delete") and a record, given as on the command line.
+type Operation struct {
+	Action string
+	Record string
+}
+
+// An OperationResult is the result of an Operation of a batch.
+type OperationResult struct {
+	Operation
+	Zone string // the zone of the record, if it was found
+	Err  error
+}
+
+var operationActions = map[string]bool{
+	"replace": true,
+	"append":  true,
+	"remove":  true,
+	"delete":  true,
+}
+
+// Batch reads operations from ops until it is closed, merges them into one
+// update per zone and sends those, continuing with the other zones if one
+// fails (as the batch command does). It returns the result of each
+// operation, in order: an operation fails if it is invalid, its zone can't
+// be found, or the update of its zone fails.
+//
+// Nothing is sent before ops is closed, as any later operation can change
+// the update of its zone, and the zones are then updated one at a time:
+// the settings and caches are shared, and sendUpdate sends one update at a
+// time anyway.
+func (u *Updater) Batch(ops <-chan Operation) []OperationResult {
+	var results []OperationResult
+	for op := range ops {
+		results = append(results, OperationResult{Operation: op})
+	}
+
+	apiLock.Lock()
+	defer apiLock.Unlock()
+
+	// Config errors before anything is sent fail the operations not
+	// failed already
+	if err := u.batch(results); err != nil {
+		for i := range results {
+			if results[i].Err == nil {
+				results[i].Err = err
+			}
+		}
+	}
+	return results
+}
+
+func (u *Updater) batch(results []OperationResult) (err error) {
+	defer recoverError(&err)
+
+	var valid []operation
+	var index []int // the result of each operation in valid
+	for i := range results {
+		res := &results[i]
+		action := strings.ToLower(res.Action)
+		if !operationActions[action] {
+			res.Err = kindError(ErrValidation, fmt.Errorf("unknown action: %s", res.Action))
+			continue
+		}
+		rr, err := parseRecord(res.Record, action)
+		if err != nil {
+			res.Err = kindError(ErrValidation, err)
+			continue
+		}
+		// Find the zones first, so groupByZone can't fail
+		if res.Zone, res.Err = u.zoneOf(rr.Header().Name); res.Err != nil {
+			continue
+		}
+		valid = append(valid, operation{action, rr})
+		index = append(index, i)
+	}
+	if len(valid) == 0 {
+		return nil
+	}
+
+	byZone := make(map[string]error)
+	for _, zu := range sendBatch(valid) {
+		byZone[zu.zone] = zu.err
+	}
+	for _, i := range index {
+		results[i].Err = byZone[results[i].Zone]
+	}
+	return nil
+}
+
+func (u *Updater) zoneOf(name string) (zone string, err error) {
+	defer recoverError(&err)
+	return getZone(name), nil
+}
+
+// Replace replaces the RRset of record with record, which is given as on
+// the command line, e.g. "test.example.org 300 A 127.0.0.1".
+func (u *Updater) Replace(record string) error {
+	return u.send("replace", record)
+}
+
+// Append adds record to its RRset.
+func (u *Updater) Append(record string) error {
+	return u.send("append", record)
+}
+
+// Remove removes the single record from its RRset.
+func (u *Updater) Remove(record string) error {
+	return u.send("remove", record)
+}
+
+// Delete deletes the RRset of type rrtype at name, or all the records at
+// name if rrtype is empty.
+func (u *Updater) Delete(name, rrtype string) error {
+	if len(rrtype) > 0 {
+		name += " " + rrtype
+	}
+	return u.send("delete", name)
+}
@@ -0,0 +1,184 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"bytes"
+	"encoding/json"
+	"fmt"
+	"log"
+	"net/http"
+	"os"
+	"strings"
+	"time"
+
+	"github.com/miekg/dns"
+	"github.com/spf13/cast"
+	"github.com/spf13/viper"
+)
+
+var vaultClient = &http.Client{Timeout: 30 * time.Second}
+
+// vaultRequest sends a request to the Vault HTTP API and decodes the JSON
+// response into v.
+func vaultRequest(method, path, token string, body interface{}, v interface{}) error {
+	addr := viper.GetString("vault.address")
+	if len(addr) == 0 {
+		addr = os.Getenv("VAULT_ADDR")
+	}
+	if len(addr) == 0 {
+		return fmt.Errorf("no Vault address configured")
+	}
+
+	var data []byte
+	if body != nil {
+		var err error
+		if data, err = json.Marshal(body); err != nil {
+			return err
+		}
+	}
+
+	req, err := http.NewRequest(method, strings.TrimRight(addr, "/")+"/v1/"+path, bytes.NewReader(data))
+	if err != nil {
+		return err
+	}
+	if len(token) > 0 {
+		req.Header.Set("X-Vault-Token", token)
+	}
+
+	resp, err := vaultClient.Do(req)
+	if err != nil {
+		return err
+	}
+	defer resp.Body.Close()
+
+	if resp.StatusCode != http.StatusOK {
+		return fmt.Errorf("%s returned %s", path, resp.Status)
+	}
+	return json.NewDecoder(resp.Body).Decode(v)
+}
+
+// vaultToken returns the token to authenticate to Vault with: the
+// configured token, the token in VAULT_TOKEN, or one obtained by logging
+// in with the configured AppRole.
+func vaultToken() (string, error) {
+	if token := viper.GetString("vault.token"); len(token) > 0 {
+		return token, nil
+	}
+	if token := os.Getenv("VAULT_TOKEN"); len(token) > 0 {
+		return token, nil
+	}
+
+	roleID := viper.GetString("vault.role-id")
+	if len(roleID) == 0 {
+		return "", fmt.Errorf("no Vault token or AppRole configured")
+	}
+	secretID := viper.GetString("vault.secret-id")
+	if file := viper.GetString("vault.secret-id-file"); len(file) > 0 {
+		data, err := os.ReadFile(file)
+		if err != nil {
+			return "", err
+		}
+		secretID = strings.TrimSpace(string(data))
+	}
+
+	var login struct {
+		Auth struct {
+			ClientToken string `json:"client_token"`
+		} `json:"auth"`
+	}
+	body := map[string]string{"role_id": roleID, "secret_id": secretID}
+	path := "auth/" + viper.GetString("vault.approle-mount") + "/login"
+	if err := vaultRequest("POST", path, "", body, &login); err != nil {
+		return "", fmt.Errorf("AppRole login failed: %s", err)
+	}
+	return login.Auth.ClientToken, nil
+}
+
+// readVaultKey reads a TSIG key from a Vault KV secret (version 1 or 2),
+// with the fields "secret" and optionally "name" and "algorithm".
+func readVaultKey(path string) (*bindKey, error) {
+	token, err := vaultToken()
+	if err != nil {
+		return nil, err
+	}
+
+	var secret struct {
+		Data map[string]interface{} `json:"data"`
+	}
+	if err := vaultRequest("GET", path, token, nil, &secret); err != nil {
+		return nil, err
+	}
+
+	// Version 2 of the KV engine wraps the data with its metadata
+	data := secret.Data
+	if inner, ok := data["data"].(map[string]interface{}); ok {
+		if _, ok := data["metadata"]; ok {
+			data = inner
+		}
+	}
+
+	key := &bindKey{
+		name:      cast.ToString(data["name"]),
+		algorithm: cast.ToString(data["algorithm"]),
+		secret:    cast.ToString(data["secret"]),
+	}
+	if len(key.secret) == 0 {
+		return nil, fmt.Errorf("%s has no secret field", path)
+	}
+	if len(key.name) > 0 {
+		key.name = dns.Fqdn(key.name)
+	}
+	if len(key.algorithm) > 0 {
+		if _, ok := lookupTsigAlgorithm(key.algorithm); !ok {
+			return nil, fmt.Errorf("%s: unknown algorithm %s", path, key.algorithm)
+		}
+	}
+	return key, nil
+}
+
+type vaultEntry struct {
+	key     *bindKey
+	fetched time.Time
+}
+
+// Keys read from Vault so far. They are read again after vault.refresh,
+// so long-running commands pick up rotated keys.
+var vaultKeys = make(map[string]vaultEntry)
+
+// vaultKey returns the key to use for name from the Vault path given by
+// tsig-vault-path, or nil if there is none.
+func vaultKey(name string) *bindKey {
+	path := strings.Trim(setting(name, "tsig-vault-path"), "/")
+	if len(path) == 0 {
+		return nil
+	}
+
+	entry, ok := vaultKeys[path]
+	if !ok || time.Since(entry.fetched) > viper.GetDuration("vault.refresh") {
+		key, err := readVaultKey(path)
+		if err != nil {
+			if ok {
+				log.Printf("Unable to refresh the TSIG key from Vault, using the old one: %s", err)
+				return entry.key
+			}
+			log.Panicf("Unable to read the TSIG key from Vault: %s", err)
+		}
+		entry = vaultEntry{key, time.Now()}
+		vaultKeys[path] = entry
+	}
+	return entry.key
+}
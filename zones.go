@@ -0,0 +1,159 @@
+// Copyright (c) 2026, the update-dns contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+package updatedns
+
+import (
+	"fmt"
+	"log"
+	"strings"
+
+	"github.com/miekg/dns"
+	"github.com/spf13/viper"
+)
+
+// A ZoneStrategy finds the zone containing a (fully qualified, lower case)
+// name.
+type ZoneStrategy interface {
+	FindZone(name string) (string, error)
+}
+
+// ZoneStrategyFunc makes a function a ZoneStrategy.
+type ZoneStrategyFunc func(name string) (string, error)
+
+func (f ZoneStrategyFunc) FindZone(name string) (string, error) {
+	return f(name)
+}
+
+// The built-in strategies, selected with zone-discovery
+var (
+	// SOAStrategy finds the zone from the SOA record the server returns
+	// for the name or its parents (the default).
+	SOAStrategy ZoneStrategy = ZoneStrategyFunc(soaZone)
+
+	// NSStrategy finds the zone as the closest enclosing name with NS
+	// records on the server.
+	NSStrategy ZoneStrategy = ZoneStrategyFunc(nsZone)
+
+	// StaticStrategy finds the zone in the zones section of the config,
+	// without any queries.
+	StaticStrategy ZoneStrategy = ZoneStrategyFunc(staticZone)
+)
+
+var zoneStrategies = map[string]ZoneStrategy{
+	"soa":    SOAStrategy,
+	"ns":     NSStrategy,
+	"static": StaticStrategy,
+}
+
+// Set with Updater.SetZoneStrategy, overriding zone-discovery.
+var customStrategy ZoneStrategy
+
+// ZoneInfo describes the zone found for a name.
+type ZoneInfo struct {
+	Name     string // the zone
+	Server   string // the server its updates are sent to
+	Strategy string // the strategy that found it ("soa", "ns", "static", "custom" or "zone" for --zone)
+}
+
+func soaZone(name string) (zone string, err error) {
+	defer recoverError(&err)
+	return discoverZone(name)
+}
+
+func nsZone(name string) (string, error) {
+	for off, end := 0, false; !end; off, end = dns.NextLabel(name, off) {
+		rrs, err := lookup(name[off:], dns.TypeNS)
+		if err != nil {
+			return "", err
+		}
+		for _, rr := range rrs {
+			if ns, ok := rr.(*dns.NS); ok && strings.EqualFold(ns.Hdr.Name, name[off:]) {
+				return strings.ToLower(ns.Hdr.Name), nil
+			}
+		}
+	}
+	return "", fmt.Errorf("no NS records found for %s or its parents", name)
+}
+
+func staticZone(name string) (string, error) {
+	for off, end := 0, false; !end; off, end = dns.NextLabel(name, off) {
+		if _, ok := zoneRoutes[name[off:]]; ok {
+			return name[off:], nil
+		}
+	}
+	return "", fmt.Errorf("%s isn't in any of the zones in the config", name)
+}
+
+// checkZoneDiscovery checks the zone-discovery setting: a comma separated
+// list of strategies, tried in turn.
+func checkZoneDiscovery() {
+	for _, s := range strings.Split(viper.GetString("zone-discovery"), ",") {
+		if _, ok := zoneStrategies[strings.TrimSpace(s)]; !ok {
+			log.Panicf("Unknown zone-discovery strategy: %s (use soa, ns or static)", s)
+		}
+	}
+}
+
+// findZone finds the zone containing name with the custom strategy, or the
+// ones in zone-discovery, returning the zone and the strategy that found it.
+func findZone(name string) (string, string, error) {
+	if customStrategy != nil {
+		zone, err := customStrategy.FindZone(name)
+		return strings.ToLower(dns.Fqdn(zone)), "custom", err
+	}
+
+	strategies := strings.Split(viper.GetString("zone-discovery"), ",")
+	var errs []string
+	var s string
+	var err error
+	for _, s = range strategies {
+		s = strings.TrimSpace(s)
+		var zone string
+		if zone, err = zoneStrategies[s].FindZone(name); err == nil {
+			return zone, s, nil
+		} else if len(strategies) == 1 {
+			return "", "", err
+		}
+		errs = append(errs, fmt.Sprintf("%s: %s; ", s, err))
+	}
+	// The error of the last strategy is wrapped, so a network failure
+	// still exits with exitNetwork
+	errs = errs[:len(errs)-1]
+	return "", "", fmt.Errorf("%s%s: %w", strings.Join(errs, ""), s, err)
+}
+
+// SetZoneStrategy makes zones be found with s instead of the strategies in
+// zone-discovery (or with those again, if s is nil).
+func (u *Updater) SetZoneStrategy(s ZoneStrategy) {
+	apiLock.Lock()
+	defer apiLock.Unlock()
+	customStrategy = s
+	zoneCache = make(map[string]string)
+}
+
+// FindZone returns the zone containing name, where its updates go and how
+// it was found.
+func (u *Updater) FindZone(name string) (info ZoneInfo, err error) {
+	apiLock.Lock()
+	defer apiLock.Unlock()
+	defer recoverError(&err)
+
+	name = strings.ToLower(expandName(name))
+	info.Name = getZone(name)
+	info.Server = setting(info.Name, "server")
+	info.Strategy = zoneMethods[name]
+	return info, nil
+}